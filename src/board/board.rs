@@ -1,7 +1,20 @@
 use ansi_term::Colour;
 
+use crate::board::error::{MoveError, ParseError};
+use crate::board::rules::Rules;
+use crate::board::shape::{BoardShape, MAX_SQUARES};
+use crate::game::game::Game;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The win length used when a position is given without one, shortened on
+/// boards too small for three in a row.
+const DEFAULT_WIN_LENGTH: usize = 3;
+
+/// Heuristic weight of a line holding only one player's marks, by how many
+/// it holds, for depth-limited searches of boards too big to solve.
+const LINE_SCORES: [i32; 4] = [0, 1, 8, 64];
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Move {
     pub row: usize,
     pub col: usize,
@@ -13,32 +26,13 @@ impl Move {
         Move { row, col }
     }
 
-    pub fn to_string_on_board(&self, board: &Board) -> Result<String, String> {
+    pub fn to_string_on_board(&self, board: &Board) -> Result<String, MoveError> {
         let player = match board.get_active_player() {
             Some(player) => player,
-            None => return Err("Game is already over".to_string()),
+            None => return Err(MoveError::GameOver),
         };
-        let maybe_board = board.with_move_made(player, *self);
-        let final_board = match maybe_board {
-            Ok(b) => b,
-            Err(_) => {return Err("Invalid move".to_string());},
-        };
-        let mut to_print = "".to_string();
-        for row in 0..3 {
-            for col in 0..3 {
-                if row == self.row && col == self.col {
-                    to_print.push_str(&(Colour::Green.prefix().to_string() + &player.to_string() + &Colour::Green.suffix().to_string()));
-                } else if final_board.x_bitboard.is_set(row, col) {
-                    to_print.push_str("X");
-                } else if final_board.o_bitboard.is_set(row, col) {
-                    to_print.push_str("O");
-                } else {
-                    to_print.push_str("_");
-                }
-            }
-            to_print.push_str("\n");
-        }
-        return Ok(to_print.trim_end().to_string());
+        let final_board = board.with_move_made(player, *self)?;
+        Ok(final_board.to_string_with_square_highlighted(self.row, self.col))
     }
 
     pub fn get_row(&self) -> usize {
@@ -49,123 +43,210 @@ impl Move {
         self.col
     }
 
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         format!("({}, {})", self.row, self.col)
     }
 
-    pub fn from_string(s: &str) -> Result<Move, String> {
-        let stripped_s = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
-        if stripped_s.len() != 2 {
-            return Err(format!("Invalid move string: {}", s));
-        }
-        let mut chars = stripped_s.chars();
-        let row = match chars.next() {
-            Some('0') => 0,
-            Some('1') => 1,
-            Some('2') => 2,
-            _ => return Err(format!("Invalid move string: {}", s)),
+    /// Names just the column, which is all a move chooses under gravity.
+    pub fn to_column_string(&self) -> String {
+        format!("column {}", self.col)
+    }
+
+    /// Parses a row and column separated by whitespace or a comma, like
+    /// `"1 2"` or `"10,3"`, or written as two adjacent digits like `"12"`.
+    pub fn from_string(s: &str) -> Result<Move, ParseError> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        let coordinates = s.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|coordinate| !coordinate.is_empty())
+            .collect::<Vec<&str>>();
+        let (row, col) = match coordinates.as_slice() {
+            [row, col] => (*row, *col),
+            [digits] if digits.len() == 2 && digits.is_ascii() => digits.split_at(1),
+            _ => return Err(invalid()),
         };
-        let col = match chars.next() {
-            Some('0') => 0,
-            Some('1') => 1,
-            Some('2') => 2,
-            _ => return Err(format!("Invalid move string: {}", s)),
+        let parse_coordinate = |coordinate: &str| match coordinate.chars().all(|c| c.is_ascii_digit()) {
+            true => coordinate.parse::<usize>().map_err(|_| invalid()),
+            false => Err(invalid()),
         };
-        Ok(Move { row, col })
+        Ok(Move { row: parse_coordinate(row)?, col: parse_coordinate(col)? })
     }
 
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Board {
     x_bitboard: Bitboard,
     o_bitboard: Bitboard,
+    blocked_bitboard: Bitboard,
+    shape: BoardShape,
+    rules: Rules,
 }
 
 impl Board {
 
     pub fn empty() -> Board {
-        Board {
-            x_bitboard: Bitboard::empty(),
-            o_bitboard: Bitboard::empty(),
-        }
+        Board::empty_with_shape(BoardShape::standard())
+    }
+
+    pub fn empty_with_shape(shape: BoardShape) -> Board {
+        Board::new_with_shape(Bitboard::empty(), Bitboard::empty(), shape)
     }
 
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         let mut to_return = "".to_string();
-        for row in 0..3 {
-            for col in 0..3 {
-                if self.x_bitboard.is_set(row, col) {
-                    to_return += "X";
-                } else if self.o_bitboard.is_set(row, col) {
-                    to_return += "O";
-                } else {
-                    to_return += "_";
-                }
+        for row in 0..self.shape.get_height() {
+            for col in 0..self.shape.get_width() {
+                to_return += self.get_square_string(row, col);
             }
             to_return += "\n";
         }
-        return to_return.trim_end().to_string();
+        to_return.trim_end().to_string()
     }
 
     pub fn to_string_with_square_highlighted(&self, row: usize, col: usize) -> String {
         let mut to_return = "".to_string();
-        for r in 0..3 {
-            for c in 0..3 {
-                let to_add = if self.x_bitboard.is_set(r, c) {
-                    "X"
-                } else if self.o_bitboard.is_set(r, c) {
-                    "O"
-                } else {
-                    "_"
-                };
+        for r in 0..self.shape.get_height() {
+            for c in 0..self.shape.get_width() {
+                let to_add = self.get_square_string(r, c);
                 if r == row && c == col {
-                    to_return += &(Colour::Green.prefix().to_string() + &to_add.to_string() + &Colour::Green.suffix().to_string());
+                    to_return += &(Colour::Green.prefix().to_string() + to_add + &Colour::Green.suffix().to_string());
                 } else {
-                    to_return += &to_add;
+                    to_return += to_add;
                 }
             }
             to_return += "\n";
         }
-        return to_return.trim_end().to_string();
+        to_return.trim_end().to_string()
+    }
+
+    fn get_square_string(&self, row: usize, col: usize) -> &'static str {
+        let square = self.shape.get_square(row, col);
+        if self.x_bitboard.is_square_set(square) {
+            "X"
+        } else if self.o_bitboard.is_square_set(square) {
+            "O"
+        } else if self.blocked_bitboard.is_square_set(square) {
+            "#"
+        } else {
+            "_"
+        }
     }
     
+    /// A standard 3x3 board with the given marks.
     pub fn new(x_bitboard: Bitboard, o_bitboard: Bitboard) -> Board {
-        Board { x_bitboard, o_bitboard }
+        Board::new_with_shape(x_bitboard, o_bitboard, BoardShape::standard())
+    }
+
+    pub fn new_with_shape(x_bitboard: Bitboard, o_bitboard: Bitboard, shape: BoardShape) -> Board {
+        Board { x_bitboard, o_bitboard, blocked_bitboard: Bitboard::empty(), shape, rules: Rules::standard() }
     }
 
-    pub fn from_position(position: &str) -> Result<Self, String> {
-        let mut x_bitboard = Bitboard::empty();
-        let mut o_bitboard = Bitboard::empty();
-        let mut row = 0;
-        let mut col = 0;
-        let stripped_position = position.chars().filter(|c| !c.is_whitespace()).collect::<String>();
-        if stripped_position.len() != 9 {
-            return Err(format!("Invalid position string: {}", position));
+    /// The same position with `blocked_bitboard` as its blocked squares,
+    /// which neither player can mark, so no line through them can be
+    /// completed.  Only empty squares on the board can be blocked.
+    pub fn with_blocked_squares(&self, blocked_bitboard: Bitboard) -> Result<Board, MoveError> {
+        if let Some(square) = blocked_bitboard.difference(&self.shape.get_full_bitboard()).get_squares().first() {
+            return Err(MoveError::OutOfBounds(self.shape.get_move(*square)));
         }
-        for c in stripped_position.chars() {
-            match c {
-                'X' => x_bitboard.set(col, row),
-                'O' => o_bitboard.set(col, row),
-                '_' => (),
-                _ => return Err(format!("Invalid character: {}", c)),
-            };
-            if row == 2 {
-                row = 0;
-                col += 1;
-            } else {
-                row += 1;
+        if let Some(square) = blocked_bitboard.intersection(&self.x_bitboard.union(&self.o_bitboard)).get_squares().first() {
+            return Err(MoveError::SquareOccupied(self.shape.get_move(*square)));
+        }
+        Ok(Board { blocked_bitboard, ..*self })
+    }
+
+    /// The same position played under different rules.
+    pub fn with_rules(&self, rules: Rules) -> Board {
+        Board { rules, ..*self }
+    }
+
+    pub fn get_x_bitboard(&self) -> Bitboard {
+        self.x_bitboard
+    }
+
+    pub fn get_o_bitboard(&self) -> Bitboard {
+        self.o_bitboard
+    }
+
+    pub fn get_blocked_bitboard(&self) -> Bitboard {
+        self.blocked_bitboard
+    }
+
+    pub fn get_shape(&self) -> BoardShape {
+        self.shape
+    }
+
+    /// The shape's lines that don't pass through a blocked square, which are
+    /// the only ones that can ever be completed.
+    pub fn get_open_win_masks(&self) -> impl Iterator<Item = &'static Bitboard> + '_ {
+        self.shape.get_win_masks().iter()
+            .filter(|mask| mask.intersection(&self.blocked_bitboard).is_empty())
+    }
+
+    pub fn get_rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Parses a position with rows separated by `/` or whitespace, such as
+    /// `"XO__/_X__/____/___O"` or one row per line, taking the board's width
+    /// and height from the rows.  A position with no separators is read as
+    /// 3x3.  Boards are won with three in a row, or with a full row on boards
+    /// too small for that.  Squares marked `#` are blocked for both players.
+    pub fn from_position(position: &str) -> Result<Self, ParseError> {
+        Board::parse_position(position, None)
+    }
+
+    pub fn from_position_with_win_length(position: &str, win_length: usize) -> Result<Self, ParseError> {
+        Board::parse_position(position, Some(win_length))
+    }
+
+    fn parse_position(position: &str, win_length: Option<usize>) -> Result<Self, ParseError> {
+        let mut rows = position.split(|c: char| c == '/' || c.is_whitespace())
+            .filter(|row| !row.is_empty())
+            .map(|row| row.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+        if rows.len() <= 1 {
+            let squares = rows.concat();
+            if squares.len() != 9 {
+                return Err(ParseError::InvalidPositionLength { position: position.to_string(), length: squares.len() });
+            }
+            rows = squares.chunks(3).map(|row| row.to_vec()).collect();
+        }
+        let width = rows[0].len();
+        let height = rows.len();
+        for (row, squares) in rows.iter().enumerate() {
+            if squares.len() != width {
+                return Err(ParseError::InconsistentRowLength { row, length: squares.len(), expected: width });
+            }
+        }
+        let win_length = win_length.unwrap_or_else(|| DEFAULT_WIN_LENGTH.min(width.max(height)));
+        let shape = BoardShape::new(width, height, win_length)?;
+
+        let mut board = Board::empty_with_shape(shape);
+        for (row, squares) in rows.iter().enumerate() {
+            for (col, c) in squares.iter().enumerate() {
+                match c {
+                    'X' => board.x_bitboard.set_square(shape.get_square(row, col)),
+                    'O' => board.o_bitboard.set_square(shape.get_square(row, col)),
+                    '#' => board.blocked_bitboard.set_square(shape.get_square(row, col)),
+                    '_' => (),
+                    _ => return Err(ParseError::InvalidCharacter(*c)),
+                };
             }
         }
-        Ok(Board::new(x_bitboard, o_bitboard))
+        Ok(board)
     }
 
+    /// Under misère rules, completing a line is a loss, so the winner is the
+    /// other player.
     pub fn get_outcome(&self) -> Outcome {
-        let mut x_victory = false;
-        let mut o_victory = false;
-
-        x_victory = self.x_bitboard.is_victory();
-        o_victory = self.o_bitboard.is_victory();
+        let x_line = self.shape.is_victory(self.x_bitboard);
+        let o_line = self.shape.is_victory(self.o_bitboard);
+        let (x_victory, o_victory) = match self.rules.is_misere() {
+            true => (o_line, x_line),
+            false => (x_line, o_line),
+        };
 
         match (self.is_full(), x_victory, o_victory) {
             (_, true, true) => Outcome::Ambiguous,
@@ -176,30 +257,57 @@ impl Board {
         }
     }
 
+    /// Blocked squares count as filled, since nobody can ever mark them, and
+    /// so under gravity do empty squares below a blocked square, which no
+    /// mark can fall past.
     pub fn is_full(&self) -> bool {
-        self.x_bitboard.union(&self.o_bitboard) == Bitboard::full()
+        if self.rules.has_gravity() {
+            return (0..self.shape.get_width()).all(|col| self.get_move_in_column(col).is_err());
+        }
+        self.x_bitboard.union(&self.o_bitboard).union(&self.blocked_bitboard) == self.shape.get_full_bitboard()
     }
 
     fn is_set(&self, row: usize, col: usize) -> bool {
-        self.x_bitboard.is_set(row, col) || self.o_bitboard.is_set(row, col)
+        let square = self.shape.get_square(row, col);
+        self.x_bitboard.is_square_set(square) || self.o_bitboard.is_square_set(square) || self.blocked_bitboard.is_square_set(square)
     }
 
-    pub fn make_move(&mut self, player: Player, m: Move) -> Result<(), String> {
-        if self.get_active_player() != Some(player) {
-            return Err(format!("It is not {}'s turn", player.to_string()));
+    pub fn make_move(&mut self, player: Player, m: Move) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
         };
+        self.place_mark(player, m)
+    }
+
+    /// Puts `player`'s mark on an empty square without checking whose turn it
+    /// is or whether the game is over, for variants where boards are played
+    /// on out of turn.  Under gravity, the square must be the one
+    /// `get_move_in_column()` gives.
+    pub fn place_mark(&mut self, player: Player, m: Move) -> Result<(), MoveError> {
+        if !self.shape.contains(m) {
+            return Err(MoveError::OutOfBounds(m));
+        }
+        if self.blocked_bitboard.is_square_set(self.shape.get_square(m.row, m.col)) {
+            return Err(MoveError::SquareBlocked(m));
+        }
         if self.is_set(m.get_row(), m.get_column()) {
-            return Err(format!("Move {} has already been made", m.to_string()));
+            return Err(MoveError::SquareOccupied(m));
+        }
+        if self.rules.has_gravity() && self.get_move_in_column(m.col) != Ok(m) {
+            return Err(MoveError::NotLowestInColumn(m));
         }
+        let square = self.shape.get_square(m.row, m.col);
         match player {
-            Player::X => self.x_bitboard.set(m.row, m.col),
-            Player::O => self.o_bitboard.set(m.row, m.col),
+            Player::X => self.x_bitboard.set_square(square),
+            Player::O => self.o_bitboard.set_square(square),
         }
-        return Ok(());
+        Ok(())
     }
 
-    pub fn with_move_made(&self, player: Player, m: Move) -> Result<Self, String> {
-        let mut new_board = self.clone();
+    pub fn with_move_made(&self, player: Player, m: Move) -> Result<Self, MoveError> {
+        let mut new_board = *self;
         new_board.make_move(player, m)?;
         Ok(new_board)
     }
@@ -216,10 +324,35 @@ impl Board {
         }
     }
 
+    /// The square a mark dropped into column `col` lands on under gravity:
+    /// the lowest empty one, or the one just above the highest blocked
+    /// square when that is lower down.
+    pub fn get_move_in_column(&self, col: usize) -> Result<Move, MoveError> {
+        if col >= self.shape.get_width() {
+            return Err(MoveError::OutOfBounds(Move::new(0, col)));
+        }
+        (0..self.shape.get_height())
+            .take_while(|row| !self.is_set(*row, col))
+            .last()
+            .map(|row| Move::new(row, col))
+            .ok_or(MoveError::ColumnFull(col))
+    }
+
+    /// Every empty square in reading order, or under gravity the lowest empty
+    /// square of each column that isn't full, from the centre columns
+    /// outwards since those tend to be strongest.
     pub fn get_legal_moves(&self) -> Vec<Move> {
+        if self.rules.has_gravity() {
+            let width = self.shape.get_width();
+            let mut columns = (0..width).collect::<Vec<usize>>();
+            columns.sort_by_key(|col| (2 * col).abs_diff(width - 1));
+            return columns.into_iter()
+                .filter_map(|col| self.get_move_in_column(col).ok())
+                .collect();
+        }
         let mut moves = Vec::new();
-        for row in 0..3 {
-            for col in 0..3 {
+        for row in 0..self.shape.get_height() {
+            for col in 0..self.shape.get_width() {
                 if !self.is_set(row, col) {
                     moves.push(Move::new(row, col));
                 }
@@ -227,11 +360,60 @@ impl Board {
         }
         moves
     }
+
+    /// Scores lines that only one player has marks on, from X's perspective,
+    /// with lines nearer completion worth much more.  Under misère rules
+    /// completing a line loses, so the score is reversed.
+    pub fn get_heuristic(&self) -> i32 {
+        let mut score = 0;
+        for mask in self.get_open_win_masks() {
+            let n_x = self.x_bitboard.intersection(mask).n_set();
+            let n_o = self.o_bitboard.intersection(mask).n_set();
+            if n_o == 0 {
+                score += LINE_SCORES[n_x.min(3)];
+            }
+            if n_x == 0 {
+                score -= LINE_SCORES[n_o.min(3)];
+            }
+        }
+        match self.rules.is_misere() {
+            true => -score,
+            false => score,
+        }
+    }
     
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
-pub struct Bitboard(u16);
+impl Game for Board {
+    type Move = Move;
+
+    fn get_outcome(&self) -> Outcome {
+        Board::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        Board::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<Move> {
+        Board::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: Move) -> Self {
+        let player = Board::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    fn get_heuristic(&self) -> i32 {
+        Board::get_heuristic(self)
+    }
+}
+
+/// A set of squares, one bit per square in reading order.  Bitboards don't
+/// know the dimensions of their board; `BoardShape` maps rows and columns to
+/// squares.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bitboard(u64);
 
 impl Bitboard {
 
@@ -243,41 +425,25 @@ impl Bitboard {
         self.0 == 0
     }
 
-    pub fn full() -> Self {
-        Bitboard(0b111111111)
+    pub fn to_bits(&self) -> u64 {
+        self.0
     }
 
-    pub fn is_victory(&self) -> bool {
-        if self.contains(Bitboard(0b111000000)) { return true;}
-        if self.contains(Bitboard(0b000111000)) { return true;}
-        if self.contains(Bitboard(0b000000111)) { return true;}
-        if self.contains(Bitboard(0b100100100)) { return true;}
-        if self.contains(Bitboard(0b010010010)) { return true;}
-        if self.contains(Bitboard(0b001001001)) { return true;}
-        if self.contains(Bitboard(0b100010001)) { return true;}
-        if self.contains(Bitboard(0b001010100)) { return true;}
-        return false;
+    pub fn from_bits(bits: u64) -> Self {
+        Bitboard(bits)
     }
 
-    pub fn from_binary(binary: &str) -> Result<Self, String> {
-        if binary.len() != 9 {
-            return Err(format!("Binary string must be 9 characters long, got {}", binary.len()));
+    /// Parses a string of `0`s and `1`s, one per square in reading order.
+    pub fn from_binary(binary: &str) -> Result<Self, ParseError> {
+        if binary.is_empty() || binary.len() > MAX_SQUARES {
+            return Err(ParseError::InvalidBinaryLength(binary.len()));
         }
         let mut bitboard = Bitboard::empty();
-        let mut index = 0;
-        let mut i = 0;
-        let mut j = 0;
-        for c in binary.chars() {
+        for (square, c) in binary.chars().enumerate() {
             match c {
                 '0' => (),
-                '1' => bitboard.set(i, j),
-                _ => return Err(format!("Invalid character in binary string: {}", c)),
-            }
-            if j == 2 {
-                j = 0;
-                i += 1;
-            } else {
-                j += 1;
+                '1' => bitboard.set_square(square),
+                _ => return Err(ParseError::InvalidCharacter(c)),
             }
         }
         Ok(bitboard)
@@ -299,16 +465,25 @@ impl Bitboard {
         self.intersection(&other) == other
     }
 
-    pub fn set(&mut self, row: usize, col: usize) {
-        self.0 |= 1 << ((2 - row) * 3 + (2 - col));
+    pub fn set_square(&mut self, square: usize) {
+        self.0 |= 1 << square;
+    }
+
+    pub fn clear_square(&mut self, square: usize) {
+        self.0 &= !(1 << square);
     }
 
     pub fn n_set(&self) -> usize {
         self.0.count_ones() as usize
     }
 
-    pub fn is_set(&self, row: usize, col: usize) -> bool {
-        self.0 & (1 << ((2 - row) * 3 + (2 - col))) != 0
+    pub fn is_square_set(&self, square: usize) -> bool {
+        self.0 & (1 << square) != 0
+    }
+
+    /// The set squares, in increasing order.
+    pub fn get_squares(&self) -> Vec<usize> {
+        (0..MAX_SQUARES).filter(|square| self.is_square_set(*square)).collect()
     }
 
 }
@@ -324,6 +499,7 @@ pub enum Outcome {
 
 impl Outcome {
 
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         match self {
             Outcome::Victory(player) => format!("{} wins", player.to_string()),
@@ -334,7 +510,7 @@ impl Outcome {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Player {
     X,
     O,
@@ -342,6 +518,14 @@ pub enum Player {
 
 impl Player {
 
+    pub fn other(&self) -> Player {
+        match self {
+            Player::X => Player::O,
+            Player::O => Player::X,
+        }
+    }
+
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         match self {
             Player::X => "X".to_string(),
@@ -353,6 +537,8 @@ impl Player {
 #[cfg(test)]
 mod test_board_tests {
     use super::*;
+    use crate::board::error::ShapeError;
+    use crate::board::symmetry::Symmetry;
 
     #[test]
     fn test_board_instantiates() {
@@ -401,19 +587,38 @@ mod test_board_tests {
 
     }
 
+    #[test]
+    fn test_misere_board_flips_winner() {
+        let board = Board::from_position(
+            "XOX
+            OOO
+            _XX",
+        ).unwrap().with_rules(Rules::misere());
+        assert_eq!(board.get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(board.get_active_player(), None);
+
+        let board = Board::from_position(
+            "XOX
+            XXO
+            OXO",
+        ).unwrap().with_rules(Rules::misere());
+        assert_eq!(board.get_outcome(), Outcome::Draw);
+    }
+
     #[test]
     fn test_bitboard_instantiates() {
         let mut bitboard = Bitboard::empty();
         assert_eq!(bitboard, Bitboard(0));
-        bitboard.set(1, 2);
-        bitboard.set(2, 2);
+        bitboard.set_square(5);
+        bitboard.set_square(8);
         assert_eq!(bitboard, Bitboard::from_binary("000001001").unwrap());
 
         let mut bitboard_2 = Bitboard::empty();
-        bitboard_2.set(2, 1);
+        bitboard_2.set_square(1);
         assert_eq!(bitboard_2, Bitboard(2));
+        assert_eq!(bitboard_2.get_squares(), vec![1]);
 
-        let mut other_bitboard = Bitboard::from_binary("110000001").unwrap();
+        let other_bitboard = Bitboard::from_binary("110000001").unwrap();
         let combined_bitboard = bitboard.union(&other_bitboard);
         assert_eq!(combined_bitboard, Bitboard::from_binary("110001001").unwrap());
 
@@ -460,6 +665,7 @@ mod test_board_tests {
             "XOX\nOXO\nXOX".to_string());
     }
 
+    #[test]
     fn test_board_gets_active_player() {
         let board = Board::from_position(
             "___
@@ -534,6 +740,155 @@ mod test_board_tests {
     fn test_move_instantiates() {
         let m = Move::from_string("1 2").unwrap();
         assert_eq!(m, Move::new(1, 2));
+        assert_eq!(Move::from_string("12"), Ok(Move::new(1, 2)));
+        assert_eq!(Move::from_string("10,3"), Ok(Move::new(10, 3)));
+        assert_eq!(Move::from_string("1 x"), Err(ParseError::InvalidMove("1 x".to_string())));
+        assert_eq!(Move::from_string("123"), Err(ParseError::InvalidMove("123".to_string())));
+        assert_eq!(
+            Board::empty().with_move_made(Player::X, Move::from_string("1 3").unwrap()),
+            Err(MoveError::OutOfBounds(Move::new(1, 3)))
+        );
+    }
+
+    #[test]
+    fn test_boards_of_any_shape_parse_and_print() {
+        let board = Board::from_position("X___/_O__/____").unwrap();
+        assert_eq!(board.get_shape(), BoardShape::new(4, 3, 3).unwrap());
+        assert_eq!(board.to_string(), "X___\n_O__\n____".to_string());
+        assert_eq!(board.get_legal_moves().len(), 10);
+        assert_eq!(Board::from_position("XO_/_X_/__O").unwrap(), Board::from_position("XO__X___O").unwrap());
+        assert_eq!(Board::from_position("XO/_X").unwrap().get_shape(), BoardShape::new(2, 2, 2).unwrap());
+        assert_eq!(
+            Board::from_position("X___/_O_/____"),
+            Err(ParseError::InconsistentRowLength { row: 1, length: 3, expected: 4 })
+        );
+        assert_eq!(
+            Board::from_position_with_win_length("____/____", 5),
+            Err(ParseError::InvalidShape(ShapeError::InvalidWinLength { win_length: 5, max: 4 }))
+        );
+    }
+
+    #[test]
+    fn test_board_determines_winner_with_any_win_length() {
+        assert_eq!(Board::from_position(
+            "X___
+            _X__
+            __X_
+            OO_O",
+        ).unwrap().get_outcome(), Outcome::Victory(Player::X));
+
+        assert_eq!(Board::from_position_with_win_length(
+            "X___
+            _X__
+            __X_
+            OO_O",
+            4,
+        ).unwrap().get_outcome(), Outcome::InProgress);
+
+        assert_eq!(Board::from_position_with_win_length(
+            "_____
+            __O__
+            __O__
+            __O__
+            XXOXX",
+            4,
+        ).unwrap().get_outcome(), Outcome::Victory(Player::O));
+
+        let mut board = Board::empty_with_shape(BoardShape::new(7, 6, 4).unwrap());
+        for col in 0..3 {
+            board.make_move(Player::X, Move::new(5, col)).unwrap();
+            board.make_move(Player::O, Move::new(4, col)).unwrap();
+        }
+        assert_eq!(board.get_outcome(), Outcome::InProgress);
+        board.make_move(Player::X, Move::new(5, 6)).unwrap();
+        assert_eq!(board.get_outcome(), Outcome::InProgress);
+        board.make_move(Player::O, Move::new(4, 3)).unwrap();
+        assert_eq!(board.get_outcome(), Outcome::Victory(Player::O));
+    }
+
+    #[test]
+    fn test_board_rejects_illegal_moves() {
+        let mut board = Board::from_position(
+            "X__
+            ___
+            ___",
+        ).unwrap();
+        assert_eq!(board.make_move(Player::X, Move::new(1, 1)), Err(MoveError::WrongTurn(Player::X)));
+        assert_eq!(board.make_move(Player::O, Move::new(0, 0)), Err(MoveError::SquareOccupied(Move::new(0, 0))));
+
+        let board = Board::from_position(
+            "XXX
+            OO_
+            ___",
+        ).unwrap();
+        assert_eq!(board.with_move_made(Player::O, Move::new(1, 2)), Err(MoveError::GameOver));
+        assert_eq!(Board::from_position("XX_O"), Err(ParseError::InvalidPositionLength { position: "XX_O".to_string(), length: 4 }));
+        assert_eq!(Board::from_position("XX_O_____#"), Err(ParseError::InvalidPositionLength { position: "XX_O_____#".to_string(), length: 10 }));
+        assert_eq!(Board::from_position("XX_O____*"), Err(ParseError::InvalidCharacter('*')));
+    }
+
+    #[test]
+    fn test_blocked_squares_are_never_marked() {
+        let board = Board::from_position(
+            "X#_
+            _O#
+            ___",
+        ).unwrap();
+        assert_eq!(board.to_string(), "X#_\n_O#\n___");
+        assert_eq!(board.get_blocked_bitboard(), Bitboard::from_bits(0b000_100_010));
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert!(!board.get_legal_moves().contains(&Move::new(0, 1)));
+        assert_eq!(board.get_legal_moves().len(), 5);
+        assert_eq!(board.with_move_made(Player::X, Move::new(1, 2)), Err(MoveError::SquareBlocked(Move::new(1, 2))));
+        assert_eq!(board.get_open_win_masks().count(), 4);
+
+        let board = Board::from_position(
+            "XO#
+            OXX
+            XO#",
+        ).unwrap();
+        assert!(board.is_full());
+        assert_eq!(board.get_outcome(), Outcome::Draw);
+        assert_eq!(board.transformed(Symmetry::Rotate90).get_blocked_bitboard().n_set(), 2);
+        assert_eq!(board.with_blocked_squares(Bitboard::from_bits(0b1)), Err(MoveError::SquareOccupied(Move::new(0, 0))));
+        assert_eq!(Board::empty().with_blocked_squares(Bitboard::from_bits(1 << 9)), Err(MoveError::OutOfBounds(Move::new(3, 0))));
+    }
+
+    #[test]
+    fn test_gravity_stops_marks_on_blocked_squares() {
+        let board = Board::from_position(
+            "___
+            _#_
+            ___",
+        ).unwrap().with_rules(Rules::gravity());
+        assert_eq!(board.get_move_in_column(1), Ok(Move::new(0, 1)));
+        assert_eq!(board.with_move_made(Player::X, Move::new(2, 1)), Err(MoveError::NotLowestInColumn(Move::new(2, 1))));
+        let board = Board::from_position(
+            "XOX
+            O#O
+            X_X",
+        ).unwrap().with_rules(Rules::gravity());
+        assert_eq!(board.get_legal_moves(), Vec::new());
+        assert_eq!(board.get_outcome(), Outcome::Draw);
+        assert!(board.is_legal());
+    }
+
+    #[test]
+    fn test_gravity_drops_marks_to_the_bottom_of_columns() {
+        let board = Board::from_position(
+            "___
+            _O_
+            _X_",
+        ).unwrap().with_rules(Rules::gravity());
+        assert_eq!(board.get_legal_moves(), vec![Move::new(0, 1), Move::new(2, 0), Move::new(2, 2)]);
+        assert_eq!(board.get_move_in_column(2), Ok(Move::new(2, 2)));
+        assert_eq!(board.get_move_in_column(3), Err(MoveError::OutOfBounds(Move::new(0, 3))));
+        assert_eq!(board.with_move_made(Player::X, Move::new(0, 0)), Err(MoveError::NotLowestInColumn(Move::new(0, 0))));
+
+        let board = board.with_move_made(Player::X, Move::new(0, 1)).unwrap();
+        assert_eq!(board.get_move_in_column(1), Err(MoveError::ColumnFull(1)));
+        assert_eq!(board.get_legal_moves(), vec![Move::new(2, 0), Move::new(2, 2)]);
+        assert_eq!(Move::new(2, 0).to_column_string(), "column 0");
     }
 
     #[test]
@@ -0,0 +1,181 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::board::board::{Move, Player};
+use crate::board::index::MAX_INDEXED_SQUARES;
+use crate::board::shape::{BoardShape, MAX_SQUARES};
+use crate::board::validation::Illegality;
+use crate::cube::cube::CubeMove;
+use crate::gobblet::gobblet::PieceSize;
+
+/// Errors from turning strings (or raw bits) into boards, moves and
+/// evaluations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidPositionLength { position: String, length: usize },
+    InconsistentRowLength { row: usize, length: usize, expected: usize },
+    InvalidShape(ShapeError),
+    InvalidWinLength(String),
+    InvalidNumber(String),
+    InvalidCharacter(char),
+    InvalidMove(String),
+    InvalidBinaryLength(usize),
+    BitsOutsideBoard(u64),
+    InvalidEvaluation(String),
+    RepeatedNumber(u8),
+    NumberCountMismatch { n_odd: usize, n_even: usize },
+    TooManyPieces(Player),
+    InvalidStack(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidPositionLength { position, length } => write!(f, "Invalid position string: {} has {} squares, expected 9 or rows separated by '/' or whitespace", position, length),
+            ParseError::InconsistentRowLength { row, length, expected } => write!(f, "Row {} has {} squares, expected {} like the first row", row, length, expected),
+            ParseError::InvalidShape(error) => write!(f, "{}", error),
+            ParseError::InvalidWinLength(s) => write!(f, "Invalid win length: {}", s),
+            ParseError::InvalidNumber(s) => write!(f, "Invalid number: {}", s),
+            ParseError::InvalidCharacter(c) => write!(f, "Invalid character: {}", c),
+            ParseError::InvalidMove(s) => write!(f, "Invalid move string: {}", s),
+            ParseError::InvalidBinaryLength(length) => write!(f, "Binary string must have between 1 and {} characters, got {}", MAX_SQUARES, length),
+            ParseError::BitsOutsideBoard(bits) => write!(f, "Bits outside the board are set: {:#b}", bits),
+            ParseError::InvalidEvaluation(s) => write!(f, "Invalid evaluation string: {}", s),
+            ParseError::RepeatedNumber(number) => write!(f, "Number {} appears more than once", number),
+            ParseError::NumberCountMismatch { n_odd, n_even } => write!(
+                f,
+                "There are {} odd numbers and {} even ones, but odd moves first so there must be as many odd numbers as even ones or one more",
+                n_odd, n_even
+            ),
+            ParseError::TooManyPieces(player) => write!(f, "{} has more pieces than the game allows", player.to_string()),
+            ParseError::InvalidStack(s) => write!(f, "Invalid stack: {}, pieces must get strictly larger from the bottom up", s),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::InvalidShape(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ShapeError> for ParseError {
+    fn from(error: ShapeError) -> Self {
+        ParseError::InvalidShape(error)
+    }
+}
+
+/// Errors from asking for a board that can't be represented or can't be won.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeError {
+    InvalidSize { width: usize, height: usize },
+    InvalidWinLength { win_length: usize, max: usize },
+    InvalidCubeSize(usize),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShapeError::InvalidSize { width, height } => write!(f, "A {}x{} board is not supported, boards need between 1 and {} squares", width, height, MAX_SQUARES),
+            ShapeError::InvalidWinLength { win_length, max } => write!(f, "Win length must be between 1 and {}, got {}", max, win_length),
+            ShapeError::InvalidCubeSize(size) => write!(f, "A {0}x{0}x{0} cube is not supported, cubes must be 3x3x3 or 4x4x4", size),
+        }
+    }
+}
+
+impl Error for ShapeError {}
+
+/// Errors from trying to play a move that the rules don't allow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    GameOver,
+    WrongTurn(Player),
+    SquareOccupied(Move),
+    SquareBlocked(Move),
+    OutOfBounds(Move),
+    NoSuchBoard(usize),
+    BoardFinished(usize),
+    WrongBoard { board: usize, expected: usize },
+    CubeSquareOccupied(CubeMove),
+    OutOfCube(CubeMove),
+    NumberUnavailable(u8),
+    NotLowestInColumn(Move),
+    ColumnFull(usize),
+    WrongPhase,
+    NotOwnPiece(Move),
+    NotAdjacent { from: Move, to: Move },
+    NoPieceLeft(PieceSize),
+    CannotCover(Move),
+    SameSquare(Move),
+    CollapsePending,
+    NoCycle,
+    NotInCycle(Move),
+    NotLastSquare(Move),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::GameOver => write!(f, "The game is already over"),
+            MoveError::WrongTurn(player) => write!(f, "It is not {}'s turn", player.to_string()),
+            MoveError::SquareOccupied(m) => write!(f, "Move {} has already been made", m.to_string()),
+            MoveError::SquareBlocked(m) => write!(f, "Square {} is blocked", m.to_string()),
+            MoveError::OutOfBounds(m) => write!(f, "Move {} is off the board", m.to_string()),
+            MoveError::NoSuchBoard(board) => write!(f, "There is no board {}", board),
+            MoveError::BoardFinished(board) => write!(f, "Board {} is already finished", board),
+            MoveError::WrongBoard { board, expected } => write!(f, "Must play on board {}, not board {}", expected, board),
+            MoveError::CubeSquareOccupied(m) => write!(f, "Move {} has already been made", m.to_string()),
+            MoveError::OutOfCube(m) => write!(f, "Move {} is off the cube", m.to_string()),
+            MoveError::NumberUnavailable(number) => write!(f, "Number {} is not available to the player to move", number),
+            MoveError::NotLowestInColumn(m) => write!(f, "Move {} is above an empty square, but marks fall to the bottom of their column", m.to_string()),
+            MoveError::ColumnFull(col) => write!(f, "Column {} is already full", col),
+            MoveError::WrongPhase => write!(f, "Pieces are placed until each player has all of theirs on the board, and only moved after that"),
+            MoveError::NotOwnPiece(m) => write!(f, "There is no piece of the player to move at {}", m.to_string()),
+            MoveError::NotAdjacent { from, to } => write!(f, "{} is not adjacent to {}", to.to_string(), from.to_string()),
+            MoveError::NoPieceLeft(size) => write!(f, "The player to move has no {} pieces left to place", size.to_string()),
+            MoveError::CannotCover(m) => write!(f, "The piece at {} is not smaller than the one being played there", m.to_string()),
+            MoveError::SameSquare(m) => write!(f, "Both halves of a spooky mark are at {}, but they must be in different squares", m.to_string()),
+            MoveError::CollapsePending => write!(f, "The last move closed a cycle of entanglement, which must be collapsed first"),
+            MoveError::NoCycle => write!(f, "There is no cycle of entanglement to collapse"),
+            MoveError::NotInCycle(m) => write!(f, "The mark that closed the cycle is not at {}", m.to_string()),
+            MoveError::NotLastSquare(m) => write!(f, "A classical mark can only be played on the last square left, not {}", m.to_string()),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+/// Errors from ranking a board as an integer or converting an out-of-range
+/// integer back into a board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexError {
+    OutOfRange { index: usize, n_indices: usize },
+    TooManySquares(usize),
+    BlockedSquares,
+    UnsupportedShape(BoardShape),
+    IllegalPosition(Illegality),
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexError::OutOfRange { index, n_indices } => write!(f, "Board index must be less than {}, got {}", n_indices, index),
+            IndexError::TooManySquares(n_squares) => write!(f, "A board with {} squares is too large to index, the most is {}", n_squares, MAX_INDEXED_SQUARES),
+            IndexError::BlockedSquares => write!(f, "Boards with blocked squares can't be indexed"),
+            IndexError::UnsupportedShape(shape) => write!(f, "Legal indices are only defined for the standard 3x3 board, not {}", shape.to_string()),
+            IndexError::IllegalPosition(illegality) => write!(f, "{}", illegality),
+        }
+    }
+}
+
+impl Error for IndexError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IndexError::IllegalPosition(illegality) => Some(illegality),
+            IndexError::OutOfRange { .. } | IndexError::TooManySquares(_) | IndexError::BlockedSquares | IndexError::UnsupportedShape(_) => None,
+        }
+    }
+}
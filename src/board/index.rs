@@ -0,0 +1,178 @@
+use std::sync::OnceLock;
+
+use crate::board::board::{Bitboard, Board};
+use crate::board::error::IndexError;
+use crate::board::shape::BoardShape;
+
+/// The number of ways to fill the nine squares of the standard board with X,
+/// O or nothing.
+pub const N_INDICES: usize = 19683;
+
+/// The most squares a board can have and still be ranked in a `usize`.
+pub const MAX_INDEXED_SQUARES: usize = 40;
+
+/// The number of positions that can arise in a real game from the empty board.
+pub const N_LEGAL_INDICES: usize = 5478;
+
+impl Board {
+
+    /// Ranks the board in base 3, with each square contributing 0 when empty,
+    /// 1 for X and 2 for O.  The top-left square is the least significant
+    /// digit and the bottom-right square the most significant.  Boards with
+    /// more than `MAX_INDEXED_SQUARES` squares or with blocked squares can't
+    /// be ranked.
+    pub fn to_index(&self) -> Result<usize, IndexError> {
+        let n_squares = self.get_shape().n_squares();
+        if n_squares > MAX_INDEXED_SQUARES {
+            return Err(IndexError::TooManySquares(n_squares));
+        }
+        if !self.get_blocked_bitboard().is_empty() {
+            return Err(IndexError::BlockedSquares);
+        }
+        let mut index = 0;
+        for square in (0..n_squares).rev() {
+            let digit = if self.get_x_bitboard().is_square_set(square) {
+                1
+            } else if self.get_o_bitboard().is_square_set(square) {
+                2
+            } else {
+                0
+            };
+            index = index * 3 + digit;
+        }
+        Ok(index)
+    }
+
+    pub fn from_index(index: usize) -> Result<Board, IndexError> {
+        Board::from_index_with_shape(index, BoardShape::standard())
+    }
+
+    pub fn from_index_with_shape(index: usize, shape: BoardShape) -> Result<Board, IndexError> {
+        let n_squares = shape.n_squares();
+        if n_squares > MAX_INDEXED_SQUARES {
+            return Err(IndexError::TooManySquares(n_squares));
+        }
+        let n_indices = 3usize.pow(n_squares as u32);
+        if index >= n_indices {
+            return Err(IndexError::OutOfRange { index, n_indices });
+        }
+        let mut x_bitboard = Bitboard::empty();
+        let mut o_bitboard = Bitboard::empty();
+        let mut remaining = index;
+        for square in 0..n_squares {
+            match remaining % 3 {
+                1 => x_bitboard.set_square(square),
+                2 => o_bitboard.set_square(square),
+                _ => (),
+            }
+            remaining /= 3;
+        }
+        Ok(Board::new_with_shape(x_bitboard, o_bitboard, shape))
+    }
+
+    /// Ranks the board among the positions reachable from the empty board, in
+    /// increasing order of `to_index()`.  Unreachable boards are exactly the
+    /// ones `validate()` rejects, so the error explains why.  Only the
+    /// standard board has legal indices.
+    pub fn to_legal_index(&self) -> Result<usize, IndexError> {
+        if !self.get_shape().is_standard() {
+            return Err(IndexError::UnsupportedShape(self.get_shape()));
+        }
+        get_legal_indices()
+            .binary_search(&(self.to_index()? as u16))
+            .map_err(|_| IndexError::IllegalPosition(self.validate().expect_err("Boards that pass validation are reachable.")))
+    }
+
+    pub fn from_legal_index(legal_index: usize) -> Result<Board, IndexError> {
+        match get_legal_indices().get(legal_index) {
+            Some(index) => Board::from_index(*index as usize),
+            None => Err(IndexError::OutOfRange { index: legal_index, n_indices: N_LEGAL_INDICES }),
+        }
+    }
+
+}
+
+fn get_legal_indices() -> &'static [u16] {
+    static LEGAL_INDICES: OnceLock<Vec<u16>> = OnceLock::new();
+    LEGAL_INDICES.get_or_init(|| {
+        let mut is_reachable = vec![false; N_INDICES];
+        let mut to_visit = vec![Board::empty()];
+        is_reachable[Board::empty().to_index().expect("The standard board can be indexed.")] = true;
+        while let Some(board) = to_visit.pop() {
+            if let Some(player) = board.get_active_player() {
+                for m in board.get_legal_moves() {
+                    let child = board.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().");
+                    let child_index = child.to_index().expect("The standard board can be indexed.");
+                    if !is_reachable[child_index] {
+                        is_reachable[child_index] = true;
+                        to_visit.push(child);
+                    }
+                }
+            }
+        }
+        (0..N_INDICES).filter(|index| is_reachable[*index]).map(|index| index as u16).collect()
+    })
+}
+
+
+#[cfg(test)]
+mod test_index {
+    use super::*;
+    use crate::board::validation::Illegality;
+
+    #[test]
+    fn test_board_index_round_trips() {
+        assert_eq!(Board::empty().to_index(), Ok(0));
+        assert_eq!(
+            Board::from_position(
+                "XO_
+                ___
+                ___",
+            ).unwrap().to_index(),
+            Ok(1 + 2 * 3)
+        );
+        assert_eq!(
+            Board::from_position(
+                "OOO
+                OOO
+                OOO",
+            ).unwrap().to_index(),
+            Ok(N_INDICES - 1)
+        );
+        for index in 0..N_INDICES {
+            assert_eq!(Board::from_index(index).unwrap().to_index(), Ok(index));
+        }
+        assert_eq!(Board::from_index(N_INDICES), Err(IndexError::OutOfRange { index: N_INDICES, n_indices: N_INDICES }));
+
+        let shape = BoardShape::new(4, 2, 3).unwrap();
+        let board = Board::from_position("O__X/____").unwrap();
+        assert_eq!(board.to_index(), Ok(2 + 3usize.pow(3)));
+        assert_eq!(Board::from_index_with_shape(board.to_index().unwrap(), shape), Ok(board));
+        assert!(Board::from_index_with_shape(3usize.pow(8), shape).is_err());
+
+        let shape = BoardShape::new(7, 6, 4).unwrap();
+        assert_eq!(Board::empty_with_shape(shape).to_index(), Err(IndexError::TooManySquares(42)));
+        assert_eq!(Board::from_index_with_shape(0, shape), Err(IndexError::TooManySquares(42)));
+        assert_eq!(Board::from_position("X#_ ___ ___").unwrap().to_index(), Err(IndexError::BlockedSquares));
+    }
+
+    #[test]
+    fn test_legal_board_index_is_dense() {
+        assert_eq!(Board::empty().to_legal_index(), Ok(0));
+        for legal_index in 0..N_LEGAL_INDICES {
+            assert_eq!(Board::from_legal_index(legal_index).unwrap().to_legal_index(), Ok(legal_index));
+        }
+        assert!(Board::from_legal_index(N_LEGAL_INDICES).is_err());
+        assert_eq!(
+            Board::from_position(
+                "XXX
+                ___
+                OOO",
+            ).unwrap().to_legal_index(),
+            Err(IndexError::IllegalPosition(Illegality::BothPlayersWon))
+        );
+        let board = Board::from_position("____/____/____/____").unwrap();
+        assert_eq!(board.to_legal_index(), Err(IndexError::UnsupportedShape(board.get_shape())));
+    }
+
+}
@@ -1 +1,8 @@
-pub mod board;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod board;
+pub mod error;
+pub mod shape;
+pub mod rules;
+pub mod symmetry;
+pub mod index;
+pub mod validation;
\ No newline at end of file
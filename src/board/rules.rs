@@ -0,0 +1,66 @@
+/// Variations on how the game is played and scored, carried by every `Board`
+/// alongside its shape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Rules {
+    misere: bool,
+    gravity: bool,
+}
+
+impl Rules {
+
+    pub fn standard() -> Self {
+        Rules { misere: false, gravity: false }
+    }
+
+    /// In misère play, the player who completes a line loses instead of
+    /// winning.
+    pub fn misere() -> Self {
+        Rules::standard().with_misere(true)
+    }
+
+    /// With gravity, as in Connect Four, a mark falls to the lowest empty
+    /// square of its column, so a move only chooses the column.
+    pub fn gravity() -> Self {
+        Rules::standard().with_gravity(true)
+    }
+
+    pub fn with_misere(&self, misere: bool) -> Self {
+        let mut rules = *self;
+        rules.misere = misere;
+        rules
+    }
+
+    pub fn with_gravity(&self, gravity: bool) -> Self {
+        let mut rules = *self;
+        rules.gravity = gravity;
+        rules
+    }
+
+    pub fn is_misere(&self) -> bool {
+        self.misere
+    }
+
+    pub fn has_gravity(&self) -> bool {
+        self.gravity
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == Rules::standard()
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        let mut variations = Vec::new();
+        if self.misere {
+            variations.push("misère");
+        }
+        if self.gravity {
+            variations.push("gravity");
+        }
+        match variations.is_empty() {
+            true => "standard".to_string(),
+            false => variations.join(", "),
+        }
+    }
+
+}
@@ -0,0 +1,176 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use crate::board::board::{Bitboard, Move};
+use crate::board::error::ShapeError;
+
+/// The most squares a board can have, one per bit of a `Bitboard`.
+pub const MAX_SQUARES: usize = 64;
+
+/// The dimensions of an m,n,k-game: a board `width` squares across and
+/// `height` squares down, won by the first player to get `win_length` marks
+/// in a row horizontally, vertically or diagonally.
+///
+/// Squares are numbered in reading order, so square `row * width + col` is
+/// bit `row * width + col` of a `Bitboard`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BoardShape {
+    width: usize,
+    height: usize,
+    win_length: usize,
+}
+
+impl BoardShape {
+
+    pub fn new(width: usize, height: usize, win_length: usize) -> Result<Self, ShapeError> {
+        if width == 0 || height == 0 || width * height > MAX_SQUARES {
+            return Err(ShapeError::InvalidSize { width, height });
+        }
+        if win_length == 0 || win_length > width.max(height) {
+            return Err(ShapeError::InvalidWinLength { win_length, max: width.max(height) });
+        }
+        Ok(BoardShape { width, height, win_length })
+    }
+
+    /// Ordinary tic-tac-toe: three in a row on a 3x3 board.
+    pub fn standard() -> Self {
+        BoardShape { width: 3, height: 3, win_length: 3 }
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == BoardShape::standard()
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.width == self.height
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_win_length(&self) -> usize {
+        self.win_length
+    }
+
+    pub fn n_squares(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn contains(&self, m: Move) -> bool {
+        m.get_row() < self.height && m.get_column() < self.width
+    }
+
+    pub fn get_square(&self, row: usize, col: usize) -> usize {
+        row * self.width + col
+    }
+
+    pub fn get_move(&self, square: usize) -> Move {
+        Move::new(square / self.width, square % self.width)
+    }
+
+    pub fn get_full_bitboard(&self) -> Bitboard {
+        match self.n_squares() {
+            MAX_SQUARES => Bitboard::from_bits(u64::MAX),
+            n_squares => Bitboard::from_bits((1 << n_squares) - 1),
+        }
+    }
+
+    /// Every line of `win_length` squares on the board.  Masks are generated
+    /// once per shape and shared for the rest of the program, with each
+    /// thread remembering the last shape it asked about so that searches on
+    /// one board don't contend for the shared cache.
+    pub fn get_win_masks(&self) -> &'static [Bitboard] {
+        thread_local! {
+            static LAST_WIN_MASKS: Cell<Option<(BoardShape, &'static [Bitboard])>> = const { Cell::new(None) };
+        }
+        if let Some((shape, win_masks)) = LAST_WIN_MASKS.with(|last| last.get()) {
+            if shape == *self {
+                return win_masks;
+            }
+        }
+        static WIN_MASKS: OnceLock<RwLock<HashMap<BoardShape, &'static [Bitboard]>>> = OnceLock::new();
+        let cache = WIN_MASKS.get_or_init(|| RwLock::new(HashMap::new()));
+        let cached = cache.read().expect("Win mask cache is never poisoned.").get(self).copied();
+        let win_masks = match cached {
+            Some(win_masks) => win_masks,
+            None => *cache.write().expect("Win mask cache is never poisoned.")
+                .entry(*self)
+                .or_insert_with(|| Box::leak(self.generate_win_masks().into_boxed_slice())),
+        };
+        LAST_WIN_MASKS.with(|last| last.set(Some((*self, win_masks))));
+        win_masks
+    }
+
+    fn generate_win_masks(&self) -> Vec<Bitboard> {
+        let directions: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+        let mut win_masks = Vec::new();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                for (row_step, col_step) in directions.iter() {
+                    let last_row = row as isize + row_step * (self.win_length as isize - 1);
+                    let last_col = col as isize + col_step * (self.win_length as isize - 1);
+                    if last_row < 0 || last_row >= self.height as isize || last_col < 0 || last_col >= self.width as isize {
+                        continue;
+                    }
+                    let mut mask = Bitboard::empty();
+                    for i in 0..self.win_length as isize {
+                        let square_row = (row as isize + row_step * i) as usize;
+                        let square_col = (col as isize + col_step * i) as usize;
+                        mask.set_square(self.get_square(square_row, square_col));
+                    }
+                    win_masks.push(mask);
+                }
+            }
+        }
+        win_masks.sort();
+        win_masks.dedup();
+        win_masks
+    }
+
+    pub fn is_victory(&self, bitboard: Bitboard) -> bool {
+        self.get_win_masks().iter().any(|mask| bitboard.contains(*mask))
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("{}x{}, {} in a row", self.width, self.height, self.win_length)
+    }
+
+}
+
+impl Default for BoardShape {
+    fn default() -> Self {
+        BoardShape::standard()
+    }
+}
+
+
+#[cfg(test)]
+mod test_shape {
+    use super::*;
+
+    #[test]
+    fn test_shapes_generate_win_masks() {
+        assert_eq!(BoardShape::standard().get_win_masks().len(), 8);
+        assert_eq!(BoardShape::new(4, 4, 3).unwrap().get_win_masks().len(), 24);
+        assert_eq!(BoardShape::new(5, 5, 4).unwrap().get_win_masks().len(), 28);
+        assert_eq!(BoardShape::new(7, 6, 4).unwrap().get_win_masks().len(), 69);
+        assert_eq!(BoardShape::new(3, 1, 1).unwrap().get_win_masks().len(), 3);
+        assert_eq!(BoardShape::new(8, 8, 8).unwrap().get_full_bitboard().n_set(), 64);
+    }
+
+    #[test]
+    fn test_shapes_reject_impossible_dimensions() {
+        assert_eq!(BoardShape::new(0, 3, 3), Err(ShapeError::InvalidSize { width: 0, height: 3 }));
+        assert_eq!(BoardShape::new(9, 8, 4), Err(ShapeError::InvalidSize { width: 9, height: 8 }));
+        assert_eq!(BoardShape::new(4, 4, 5), Err(ShapeError::InvalidWinLength { win_length: 5, max: 4 }));
+        assert_eq!(BoardShape::new(4, 4, 0), Err(ShapeError::InvalidWinLength { win_length: 0, max: 4 }));
+    }
+
+}
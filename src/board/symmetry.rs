@@ -0,0 +1,237 @@
+use crate::board::board::{Bitboard, Board, Move};
+use crate::board::shape::BoardShape;
+
+/// The eight rotations and reflections of a square board (the dihedral group
+/// of order 8).  Rotations are clockwise.  Rectangular boards only have the
+/// four that keep their width and height, see `Symmetry::all_for_shape`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    FlipDiagonal,
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+
+    pub fn all() -> [Symmetry; 8] {
+        [
+            Symmetry::Identity,
+            Symmetry::Rotate90,
+            Symmetry::Rotate180,
+            Symmetry::Rotate270,
+            Symmetry::FlipHorizontal,
+            Symmetry::FlipVertical,
+            Symmetry::FlipDiagonal,
+            Symmetry::FlipAntiDiagonal,
+        ]
+    }
+
+    /// The symmetries that map a board of this shape onto itself.
+    pub fn all_for_shape(shape: &BoardShape) -> Vec<Symmetry> {
+        match shape.is_square() {
+            true => Symmetry::all().to_vec(),
+            false => vec![Symmetry::Identity, Symmetry::Rotate180, Symmetry::FlipHorizontal, Symmetry::FlipVertical],
+        }
+    }
+
+    /// The symmetries that map a board of this shape onto itself without
+    /// changing how it's played: under gravity, only mirroring the columns
+    /// keeps the floor at the bottom.
+    pub fn all_for_board(board: &Board) -> Vec<Symmetry> {
+        match board.get_rules().has_gravity() {
+            true => vec![Symmetry::Identity, Symmetry::FlipHorizontal],
+            false => Symmetry::all_for_shape(&board.get_shape()),
+        }
+    }
+
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            _ => *self,
+        }
+    }
+
+    pub fn transform_square(&self, row: usize, col: usize, shape: &BoardShape) -> (usize, usize) {
+        let last_row = shape.get_height() - 1;
+        let last_col = shape.get_width() - 1;
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::Rotate90 => (col, last_row - row),
+            Symmetry::Rotate180 => (last_row - row, last_col - col),
+            Symmetry::Rotate270 => (last_col - col, row),
+            Symmetry::FlipHorizontal => (row, last_col - col),
+            Symmetry::FlipVertical => (last_row - row, col),
+            Symmetry::FlipDiagonal => (col, row),
+            Symmetry::FlipAntiDiagonal => (last_col - col, last_row - row),
+        }
+    }
+
+}
+
+impl Move {
+
+    pub fn transformed(&self, symmetry: Symmetry, shape: &BoardShape) -> Move {
+        let (row, col) = symmetry.transform_square(self.row, self.col, shape);
+        Move::new(row, col)
+    }
+
+}
+
+impl Bitboard {
+
+    pub fn transformed(&self, symmetry: Symmetry, shape: &BoardShape) -> Bitboard {
+        let mut transformed = Bitboard::empty();
+        for square in self.get_squares() {
+            let m = shape.get_move(square).transformed(symmetry, shape);
+            transformed.set_square(shape.get_square(m.get_row(), m.get_column()));
+        }
+        transformed
+    }
+
+}
+
+impl Board {
+
+    /// Only symmetries in `Symmetry::all_for_board()` are meaningful here.
+    pub fn transformed(&self, symmetry: Symmetry) -> Board {
+        let shape = self.get_shape();
+        Board::new_with_shape(
+            self.get_x_bitboard().transformed(symmetry, &shape),
+            self.get_o_bitboard().transformed(symmetry, &shape),
+            shape,
+        ).with_rules(self.get_rules())
+            .with_blocked_squares(self.get_blocked_bitboard().transformed(symmetry, &shape))
+            .expect("Symmetries map empty squares to empty squares.")
+    }
+
+    /// Returns the representative of this board's equivalence class under the
+    /// symmetries of its shape and rules, along with the symmetry that maps
+    /// this board onto it.
+    pub fn get_canonical_form(&self) -> (Board, Symmetry) {
+        Symmetry::all_for_board(self).iter()
+            .map(|symmetry| (self.transformed(*symmetry), *symmetry))
+            .min_by_key(|(board, _)| *board)
+            .expect("There is always at least the identity symmetry.")
+    }
+
+    /// The symmetries that leave this board unchanged.
+    pub fn get_symmetries(&self) -> Vec<Symmetry> {
+        Symmetry::all_for_board(self).iter()
+            .filter(|symmetry| self.transformed(**symmetry) == *self)
+            .copied()
+            .collect()
+    }
+
+    /// Partitions `moves` into classes of moves that lead to equivalent
+    /// positions, preserving the order in which moves first appear.
+    pub fn group_equivalent_moves(&self, moves: &[Move]) -> Vec<Vec<Move>> {
+        let symmetries = self.get_symmetries();
+        let shape = self.get_shape();
+        let mut groups: Vec<Vec<Move>> = Vec::new();
+        for m in moves.iter() {
+            match groups.iter_mut().find(|group| symmetries.iter().any(|symmetry| group[0].transformed(*symmetry, &shape) == *m)) {
+                Some(group) => group.push(*m),
+                None => groups.push(vec![*m]),
+            }
+        }
+        groups
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_symmetry {
+    use super::*;
+
+    #[test]
+    fn test_symmetries_transform_boards() {
+        let board = Board::from_position(
+            "XO_
+            ___
+            __O",
+        ).unwrap();
+        assert_eq!(
+            board.transformed(Symmetry::Rotate90),
+            Board::from_position(
+                "__X
+                __O
+                O__",
+            ).unwrap()
+        );
+        assert_eq!(
+            board.transformed(Symmetry::FlipAntiDiagonal),
+            Board::from_position(
+                "O__
+                __O
+                __X",
+            ).unwrap()
+        );
+        for symmetry in Symmetry::all() {
+            assert_eq!(board.transformed(symmetry).transformed(symmetry.inverse()), board);
+            assert_eq!(
+                Move::new(0, 1).transformed(symmetry, &BoardShape::standard()).transformed(symmetry.inverse(), &BoardShape::standard()),
+                Move::new(0, 1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_equivalent_boards_share_canonical_form() {
+        let board = Board::from_position(
+            "X__
+            _O_
+            ___",
+        ).unwrap();
+        let (canonical, _) = board.get_canonical_form();
+        for symmetry in Symmetry::all() {
+            let (transformed_canonical, transformation) = board.transformed(symmetry).get_canonical_form();
+            assert_eq!(transformed_canonical, canonical);
+            assert_eq!(board.transformed(symmetry).transformed(transformation), canonical);
+        }
+    }
+
+    #[test]
+    fn test_board_groups_equivalent_moves() {
+        let board = Board::empty();
+        assert_eq!(board.get_symmetries().len(), 8);
+        assert_eq!(
+            board.group_equivalent_moves(&board.get_legal_moves()),
+            vec![
+                vec![Move::new(0, 0), Move::new(0, 2), Move::new(2, 0), Move::new(2, 2)],
+                vec![Move::new(0, 1), Move::new(1, 0), Move::new(1, 2), Move::new(2, 1)],
+                vec![Move::new(1, 1)],
+            ]
+        );
+
+        let board = Board::from_position(
+            "X__
+            ___
+            ___",
+        ).unwrap();
+        assert_eq!(board.get_symmetries(), vec![Symmetry::Identity, Symmetry::FlipDiagonal]);
+        assert_eq!(
+            board.group_equivalent_moves(&[Move::new(0, 1), Move::new(1, 1), Move::new(1, 0)]),
+            vec![vec![Move::new(0, 1), Move::new(1, 0)], vec![Move::new(1, 1)]]
+        );
+    }
+
+    #[test]
+    fn test_rectangular_boards_have_four_symmetries() {
+        let board = Board::from_position("X___/____/____").unwrap();
+        assert_eq!(Symmetry::all_for_shape(&board.get_shape()).len(), 4);
+        assert_eq!(board.transformed(Symmetry::Rotate180), Board::from_position("____/____/___X").unwrap());
+        assert_eq!(board.transformed(Symmetry::FlipHorizontal), Board::from_position("___X/____/____").unwrap());
+        assert_eq!(
+            Board::from_position("____/____/____").unwrap().group_equivalent_moves(&board.get_legal_moves())[0],
+            vec![Move::new(0, 1), Move::new(0, 2), Move::new(2, 1), Move::new(2, 2)]
+        );
+    }
+
+}
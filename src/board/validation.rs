@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+use crate::board::board::{Bitboard, Board, Move, Player};
+use crate::board::shape::BoardShape;
+
+/// The reason a position cannot arise in a real game started from the empty
+/// board with X moving first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Illegality {
+    PieceCountMismatch { n_x: usize, n_o: usize },
+    BothPlayersWon,
+    PlayContinuedAfterWin(Player),
+    SeparateLines(Player),
+    FloatingMark(Move),
+    UnreachableStacking,
+}
+
+impl fmt::Display for Illegality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Illegality::PieceCountMismatch { n_x, n_o } => write!(
+                f,
+                "X has {} marks and O has {}, but X moves first so X must have the same number of marks as O or one more",
+                n_x, n_o
+            ),
+            Illegality::BothPlayersWon => write!(f, "Both X and O have completed a line, but the game ends at the first line"),
+            Illegality::PlayContinuedAfterWin(winner) => write!(
+                f,
+                "{} has completed a line, but {} has moved since the game ended",
+                winner.to_string(), winner.other().to_string()
+            ),
+            Illegality::SeparateLines(winner) => write!(
+                f,
+                "{} has completed lines that share no square, but the game ends at the first line, so the last mark must be in all of them",
+                winner.to_string()
+            ),
+            Illegality::FloatingMark(m) => write!(f, "The mark at {} is above an empty square, but marks fall to the bottom of their column", m.to_string()),
+            Illegality::UnreachableStacking => write!(
+                f,
+                "The marks can't have been stacked up their columns by alternating moves, with any winning mark played last"
+            ),
+        }
+    }
+}
+
+impl Error for Illegality {}
+
+impl Board {
+
+    /// Checks that the position can be reached from the empty board by
+    /// alternating moves, stopping as soon as a player completes a line.
+    /// All of the winner's lines must then hold their last mark.  Under
+    /// gravity, every mark must also rest on the floor, another mark or a
+    /// blocked square, and the columns must be stackable in that order.
+    pub fn validate(&self) -> Result<(), Illegality> {
+        let n_x = self.get_x_bitboard().n_set();
+        let n_o = self.get_o_bitboard().n_set();
+        if n_x != n_o && n_x != n_o + 1 {
+            return Err(Illegality::PieceCountMismatch { n_x, n_o });
+        }
+        let shape = self.get_shape();
+        if self.get_rules().has_gravity() {
+            let marks = self.get_x_bitboard().union(&self.get_o_bitboard());
+            let occupied = marks.union(&self.get_blocked_bitboard());
+            let floating = marks.get_squares().into_iter()
+                .map(|square| shape.get_move(square))
+                .find(|m| m.get_row() + 1 < shape.get_height() && !occupied.is_square_set(shape.get_square(m.get_row() + 1, m.get_column())));
+            if let Some(m) = floating {
+                return Err(Illegality::FloatingMark(m));
+            }
+        }
+        let last_squares = match (shape.is_victory(self.get_x_bitboard()), shape.is_victory(self.get_o_bitboard())) {
+            (true, true) => return Err(Illegality::BothPlayersWon),
+            (true, false) if n_x == n_o => return Err(Illegality::PlayContinuedAfterWin(Player::X)),
+            (false, true) if n_x > n_o => return Err(Illegality::PlayContinuedAfterWin(Player::O)),
+            (true, false) => Board::get_last_mark_squares(shape, self.get_x_bitboard(), Player::X)?,
+            (false, true) => Board::get_last_mark_squares(shape, self.get_o_bitboard(), Player::O)?,
+            (false, false) if n_x > n_o => self.get_x_bitboard(),
+            (false, false) => self.get_o_bitboard(),
+        };
+        let marks = self.get_x_bitboard().union(&self.get_o_bitboard());
+        if self.get_rules().has_gravity() && !self.can_unstack(marks, last_squares, &mut HashSet::new()) {
+            return Err(Illegality::UnreachableStacking);
+        }
+        Ok(())
+    }
+
+    /// The squares the winner's last mark can be on, which are those in all of
+    /// their lines.  On boards bigger than the lines, a player can have two
+    /// lines with no square in common, which would mean the game went on
+    /// after the first.
+    fn get_last_mark_squares(shape: BoardShape, bitboard: Bitboard, winner: Player) -> Result<Bitboard, Illegality> {
+        let shared_squares = shape.get_win_masks().iter()
+            .filter(|mask| bitboard.contains(**mask))
+            .fold(bitboard, |shared, mask| shared.intersection(mask));
+        match shared_squares.is_empty() {
+            true => Err(Illegality::SeparateLines(winner)),
+            false => Ok(shared_squares),
+        }
+    }
+
+    /// Takes `marks` off the tops of their columns in reverse move order,
+    /// starting with one on `last_squares` and then alternating between the
+    /// players, to check that gravity could have stacked them.  Sets of marks
+    /// that can't be taken off are remembered in `dead_ends`.
+    fn can_unstack(&self, marks: Bitboard, last_squares: Bitboard, dead_ends: &mut HashSet<Bitboard>) -> bool {
+        if marks.is_empty() {
+            return true;
+        }
+        if dead_ends.contains(&marks) {
+            return false;
+        }
+        let shape = self.get_shape();
+        let occupied = marks.union(&self.get_blocked_bitboard());
+        let previous_squares = match self.get_x_bitboard().intersection(&last_squares).is_empty() {
+            true => self.get_x_bitboard(),
+            false => self.get_o_bitboard(),
+        };
+        let can_unstack = (0..shape.get_width())
+            .filter_map(|col| (0..shape.get_height())
+                .map(|row| shape.get_square(row, col))
+                .find(|square| occupied.is_square_set(*square)))
+            .filter(|square| marks.intersection(&last_squares).is_square_set(*square))
+            .any(|square| {
+                let mut remaining = marks;
+                remaining.clear_square(square);
+                self.can_unstack(remaining, previous_squares, dead_ends)
+            });
+        if !can_unstack {
+            dead_ends.insert(marks);
+        }
+        can_unstack
+    }
+
+    pub fn is_legal(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_validation {
+    use super::*;
+    use crate::board::index::N_INDICES;
+    use crate::board::rules::Rules;
+
+    #[test]
+    fn test_board_validation_explains_illegal_positions() {
+        assert_eq!(Board::empty().validate(), Ok(()));
+
+        assert_eq!(
+            Board::from_position(
+                "XXX
+                ___
+                OOO",
+            ).unwrap().validate(),
+            Err(Illegality::BothPlayersWon)
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "O_O
+                ___
+                ___",
+            ).unwrap().validate(),
+            Err(Illegality::PieceCountMismatch { n_x: 0, n_o: 2 })
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "XXX
+                OO_
+                O__",
+            ).unwrap().validate(),
+            Err(Illegality::PlayContinuedAfterWin(Player::X))
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "OOO
+                XX_
+                X_X",
+            ).unwrap().validate(),
+            Err(Illegality::PlayContinuedAfterWin(Player::O))
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "XXX
+                OO_
+                ___",
+            ).unwrap().validate(),
+            Ok(())
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "___
+                X__
+                _O_",
+            ).unwrap().with_rules(Rules::gravity()).validate(),
+            Err(Illegality::FloatingMark(Move::new(1, 0)))
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "___
+                X__
+                O__",
+            ).unwrap().with_rules(Rules::gravity()).validate(),
+            Err(Illegality::UnreachableStacking)
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "____
+                ____
+                OOX_
+                XXXO",
+            ).unwrap().with_rules(Rules::gravity()).validate(),
+            Err(Illegality::UnreachableStacking)
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "____
+                ____
+                O___
+                XXXO",
+            ).unwrap().with_rules(Rules::gravity()).validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_board_validation_rejects_separate_winning_lines() {
+        assert_eq!(
+            Board::from_position(
+                "XXX_
+                O_O_
+                XXX_
+                OO_O",
+            ).unwrap().validate(),
+            Err(Illegality::SeparateLines(Player::X))
+        );
+
+        assert_eq!(
+            Board::from_position(
+                "XXX_
+                X_OO
+                X_O_
+                O___",
+            ).unwrap().validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_legal_boards_are_exactly_the_reachable_ones() {
+        for index in 0..N_INDICES {
+            let board = Board::from_index(index).unwrap();
+            assert_eq!(board.is_legal(), board.to_legal_index().is_ok(), "{}", board.to_string());
+        }
+    }
+
+}
@@ -0,0 +1,406 @@
+use std::sync::OnceLock;
+
+use crate::board::board::{Bitboard, Outcome, Player};
+use crate::board::error::{MoveError, ParseError, ShapeError};
+use crate::game::game::Game;
+
+/// Heuristic weight of a line holding only one player's marks, by how many
+/// marks it holds.
+const LINE_SCORES: [i32; 4] = [0, 1, 8, 64];
+
+/// A square of a cubic board, given as layer, row and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CubeMove {
+    pub layer: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl CubeMove {
+
+    pub fn new(layer: usize, row: usize, col: usize) -> Self {
+        CubeMove { layer, row, col }
+    }
+
+    pub fn get_layer(&self) -> usize {
+        self.layer
+    }
+
+    pub fn get_row(&self) -> usize {
+        self.row
+    }
+
+    pub fn get_column(&self) -> usize {
+        self.col
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("({}, {}, {})", self.layer, self.row, self.col)
+    }
+
+    /// Parses a layer, row and column separated by whitespace or commas, like
+    /// `"1 0 2"`, or written as three adjacent digits like `"102"`.
+    pub fn from_string(s: &str) -> Result<CubeMove, ParseError> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        let coordinates = s.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|coordinate| !coordinate.is_empty())
+            .collect::<Vec<&str>>();
+        let coordinates = match coordinates.as_slice() {
+            [digits] if digits.len() == 3 && digits.is_ascii() => vec![&digits[0..1], &digits[1..2], &digits[2..3]],
+            _ => coordinates,
+        };
+        let coordinates = coordinates.iter()
+            .map(|coordinate| match coordinate.chars().all(|c| c.is_ascii_digit()) {
+                true => coordinate.parse::<usize>().map_err(|_| invalid()),
+                false => Err(invalid()),
+            })
+            .collect::<Result<Vec<usize>, ParseError>>()?;
+        match coordinates.as_slice() {
+            [layer, row, col] => Ok(CubeMove::new(*layer, *row, *col)),
+            _ => Err(invalid()),
+        }
+    }
+
+}
+
+/// 3D tic-tac-toe on a cube of `size` layers of `size` by `size` squares,
+/// won by `size` in a row along any of the 13 directions through the cube:
+/// 49 lines on the 3x3x3 cube and 76 on the 4x4x4 cube (Qubic).  Squares are
+/// numbered layer by layer in reading order, one bit each of a `Bitboard`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CubeBoard {
+    x_bitboard: Bitboard,
+    o_bitboard: Bitboard,
+    size: usize,
+}
+
+impl CubeBoard {
+
+    /// Only 3x3x3 and 4x4x4 cubes are supported; `size` must be 3 or 4.
+    pub fn empty(size: usize) -> Result<Self, ShapeError> {
+        if size != 3 && size != 4 {
+            return Err(ShapeError::InvalidCubeSize(size));
+        }
+        Ok(CubeBoard { x_bitboard: Bitboard::empty(), o_bitboard: Bitboard::empty(), size })
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn n_squares(&self) -> usize {
+        self.size * self.size * self.size
+    }
+
+    pub fn get_x_bitboard(&self) -> Bitboard {
+        self.x_bitboard
+    }
+
+    pub fn get_o_bitboard(&self) -> Bitboard {
+        self.o_bitboard
+    }
+
+    /// Parses all 27 or 64 squares layer by layer, with any `/` or whitespace
+    /// between rows and layers ignored.  The number of squares decides the
+    /// size of the cube.
+    pub fn from_position(position: &str) -> Result<Self, ParseError> {
+        let squares = position.chars().filter(|c| *c != '/' && !c.is_whitespace()).collect::<Vec<char>>();
+        let mut board = match squares.len() {
+            27 => CubeBoard::empty(3)?,
+            64 => CubeBoard::empty(4)?,
+            length => return Err(ParseError::InvalidPositionLength { position: position.to_string(), length }),
+        };
+        for (square, c) in squares.iter().enumerate() {
+            match c {
+                'X' => board.x_bitboard.set_square(square),
+                'O' => board.o_bitboard.set_square(square),
+                '_' => (),
+                _ => return Err(ParseError::InvalidCharacter(*c)),
+            }
+        }
+        Ok(board)
+    }
+
+    /// Prints each layer as a square of rows, with a blank line between
+    /// layers.
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        let mut layers = Vec::new();
+        for layer in 0..self.size {
+            let mut rows = Vec::new();
+            for row in 0..self.size {
+                rows.push(
+                    (0..self.size)
+                        .map(|col| {
+                            let square = self.get_square(CubeMove::new(layer, row, col));
+                            if self.x_bitboard.is_square_set(square) {
+                                'X'
+                            } else if self.o_bitboard.is_square_set(square) {
+                                'O'
+                            } else {
+                                '_'
+                            }
+                        })
+                        .collect::<String>()
+                );
+            }
+            layers.push(rows.join("\n"));
+        }
+        layers.join("\n\n")
+    }
+
+    pub fn contains(&self, m: CubeMove) -> bool {
+        m.layer < self.size && m.row < self.size && m.col < self.size
+    }
+
+    pub fn get_square(&self, m: CubeMove) -> usize {
+        (m.layer * self.size + m.row) * self.size + m.col
+    }
+
+    pub fn get_move(&self, square: usize) -> CubeMove {
+        CubeMove::new(square / (self.size * self.size), (square / self.size) % self.size, square % self.size)
+    }
+
+    /// Every line of `size` squares through the cube, generated once per
+    /// size.
+    pub fn get_win_masks(&self) -> &'static [Bitboard] {
+        static WIN_MASKS_3: OnceLock<Vec<Bitboard>> = OnceLock::new();
+        static WIN_MASKS_4: OnceLock<Vec<Bitboard>> = OnceLock::new();
+        match self.size {
+            3 => WIN_MASKS_3.get_or_init(|| CubeBoard::generate_win_masks(3)),
+            _ => WIN_MASKS_4.get_or_init(|| CubeBoard::generate_win_masks(4)),
+        }
+    }
+
+    fn generate_win_masks(size: usize) -> Vec<Bitboard> {
+        let board = CubeBoard::empty(size).expect("Win masks are only generated for supported cube sizes.");
+        let last = size as isize - 1;
+        let mut win_masks = Vec::new();
+        for d_layer in -1..=1 {
+            for d_row in -1..=1 {
+                for d_col in -1..=1 {
+                    // Each line is generated from one of its two directions only.
+                    if (d_layer, d_row, d_col) <= (0, 0, 0) {
+                        continue;
+                    }
+                    let starts = |step: isize| match step {
+                        1 => vec![0],
+                        -1 => vec![last],
+                        _ => (0..=last).collect(),
+                    };
+                    for layer in starts(d_layer) {
+                        for row in starts(d_row) {
+                            for col in starts(d_col) {
+                                let mut mask = Bitboard::empty();
+                                for i in 0..size as isize {
+                                    let m = CubeMove::new((layer + i * d_layer) as usize, (row + i * d_row) as usize, (col + i * d_col) as usize);
+                                    mask.set_square(board.get_square(m));
+                                }
+                                win_masks.push(mask);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        win_masks
+    }
+
+    fn is_victory(&self, bitboard: Bitboard) -> bool {
+        self.get_win_masks().iter().any(|mask| bitboard.contains(*mask))
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.x_bitboard.union(&self.o_bitboard).n_set() == self.n_squares()
+    }
+
+    pub fn get_outcome(&self) -> Outcome {
+        match (self.is_full(), self.is_victory(self.x_bitboard), self.is_victory(self.o_bitboard)) {
+            (_, true, true) => Outcome::Ambiguous,
+            (_, true, false) => Outcome::Victory(Player::X),
+            (_, false, true) => Outcome::Victory(Player::O),
+            (true, false, false) => Outcome::Draw,
+            (false, false, false) => Outcome::InProgress,
+        }
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        if self.get_outcome() != Outcome::InProgress {
+            return None;
+        }
+        if self.x_bitboard.n_set() == self.o_bitboard.n_set() {
+            Some(Player::X)
+        } else {
+            Some(Player::O)
+        }
+    }
+
+    /// Every empty square, ordered so that searches try the likeliest best
+    /// moves first: wins, then blocks of the opponent's wins, then squares on
+    /// the most promising lines for either player.
+    pub fn get_legal_moves(&self) -> Vec<CubeMove> {
+        let (own, other) = match self.get_active_player() {
+            Some(Player::X) => (self.x_bitboard, self.o_bitboard),
+            Some(Player::O) => (self.o_bitboard, self.x_bitboard),
+            None => return Vec::new(),
+        };
+        let occupied = own.union(&other);
+        let mut moves = (0..self.n_squares())
+            .filter(|square| !occupied.is_square_set(*square))
+            .map(|square| {
+                let (mut n_wins, mut n_blocks, mut score) = (0, 0, 0);
+                for mask in self.get_win_masks().iter().filter(|mask| mask.is_square_set(square)) {
+                    let n_own = own.intersection(mask).n_set();
+                    let n_other = other.intersection(mask).n_set();
+                    if n_other == 0 {
+                        n_wins += (n_own + 1 == self.size) as usize;
+                        score += LINE_SCORES[n_own.min(3)];
+                    }
+                    if n_own == 0 {
+                        n_blocks += (n_other + 1 == self.size) as usize;
+                        score += LINE_SCORES[n_other.min(3)];
+                    }
+                }
+                (std::cmp::Reverse((n_wins, n_blocks, score)), square)
+            })
+            .collect::<Vec<_>>();
+        moves.sort();
+        moves.into_iter().map(|(_, square)| self.get_move(square)).collect()
+    }
+
+    pub fn make_move(&mut self, player: Player, m: CubeMove) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        if !self.contains(m) {
+            return Err(MoveError::OutOfCube(m));
+        }
+        let square = self.get_square(m);
+        if self.x_bitboard.union(&self.o_bitboard).is_square_set(square) {
+            return Err(MoveError::CubeSquareOccupied(m));
+        }
+        match player {
+            Player::X => self.x_bitboard.set_square(square),
+            Player::O => self.o_bitboard.set_square(square),
+        }
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, player: Player, m: CubeMove) -> Result<Self, MoveError> {
+        let mut new_board = *self;
+        new_board.make_move(player, m)?;
+        Ok(new_board)
+    }
+
+    /// Scores lines that only one player has marks on, from X's perspective,
+    /// with lines nearer completion worth much more.
+    pub fn get_heuristic(&self) -> i32 {
+        let mut score = 0;
+        for mask in self.get_win_masks() {
+            let n_x = self.x_bitboard.intersection(mask).n_set();
+            let n_o = self.o_bitboard.intersection(mask).n_set();
+            if n_o == 0 {
+                score += LINE_SCORES[n_x.min(3)];
+            }
+            if n_x == 0 {
+                score -= LINE_SCORES[n_o.min(3)];
+            }
+        }
+        score
+    }
+
+}
+
+impl Game for CubeBoard {
+    type Move = CubeMove;
+
+    fn get_outcome(&self) -> Outcome {
+        CubeBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        CubeBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<CubeMove> {
+        CubeBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: CubeMove) -> Self {
+        let player = CubeBoard::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    fn get_heuristic(&self) -> i32 {
+        CubeBoard::get_heuristic(self)
+    }
+}
+
+
+#[cfg(test)]
+mod test_cube {
+    use super::*;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+    use crate::solver::depth_limited::DepthLimitedSolver;
+    use crate::solver::evaluation::Evaluation;
+
+    #[test]
+    fn test_cubes_have_every_line() {
+        assert_eq!(CubeBoard::empty(3).unwrap().get_win_masks().len(), 49);
+        assert_eq!(CubeBoard::empty(4).unwrap().get_win_masks().len(), 76);
+        assert!(CubeBoard::empty(4).unwrap().get_win_masks().iter().all(|mask| mask.n_set() == 4));
+        assert_eq!(CubeBoard::empty(5), Err(ShapeError::InvalidCubeSize(5)));
+    }
+
+    #[test]
+    fn test_cube_moves_parse_and_win() {
+        assert_eq!(CubeMove::from_string("1 0 2"), Ok(CubeMove::new(1, 0, 2)));
+        assert_eq!(CubeMove::from_string("102"), Ok(CubeMove::new(1, 0, 2)));
+        assert_eq!(CubeMove::from_string("1 0"), Err(ParseError::InvalidMove("1 0".to_string())));
+
+        let board = CubeBoard::from_position(
+            "X__ ___ ___
+            ___ _X_ OO_
+            ___ ___ ___",
+        ).unwrap();
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert_eq!(board.with_move_made(Player::X, CubeMove::new(1, 1, 1)), Err(MoveError::CubeSquareOccupied(CubeMove::new(1, 1, 1))));
+        assert_eq!(board.with_move_made(Player::X, CubeMove::new(3, 0, 0)), Err(MoveError::OutOfCube(CubeMove::new(3, 0, 0))));
+        let won = board.with_move_made(Player::X, CubeMove::new(2, 2, 2)).unwrap();
+        assert_eq!(won.get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(CubeBoard::from_position(&won.to_string()), Ok(won));
+    }
+
+    #[test]
+    fn test_first_player_wins_on_the_3x3x3_cube() {
+        let mut solver = AlphaBetaSolver::from_board(CubeBoard::empty(3).unwrap());
+        let (next_moves, evaluation) = solver.get_next_moves_and_evaluation().unwrap();
+        assert_eq!(evaluation, Evaluation::win(Player::X, 7));
+        assert_eq!(next_moves, vec![CubeMove::new(1, 1, 1)]);
+    }
+
+    #[test]
+    fn test_qubic_search_blocks_and_wins() {
+        let board = CubeBoard::from_position(
+            "XXX_ ____ ____ ____
+            OO__ ____ ____ ____
+            O___ ____ ____ ____
+            ____ ____ ____ ____",
+        ).unwrap();
+        let mut solver = DepthLimitedSolver::from_board(board, 2);
+        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![CubeMove::new(0, 0, 3)], Evaluation::win(Player::X, 1))));
+
+        let board = CubeBoard::from_position(
+            "XX__ ____ ____ ____
+            OOO_ ____ ____ ____
+            X___ ____ ____ ____
+            ____ ____ ____ ____",
+        ).unwrap();
+        let mut solver = DepthLimitedSolver::from_board(board, 2);
+        assert_eq!(solver.get_next_moves().unwrap(), vec![CubeMove::new(1, 0, 3)]);
+    }
+
+}
@@ -0,0 +1,181 @@
+use crate::board::board::{Board, Move, Outcome, Player};
+use crate::board::error::MoveError;
+use crate::game::game::Game;
+use crate::solver::evaluation::Evaluation;
+
+const MAX_MARKS: usize = 3;
+
+/// Tic-tac-toe where each player keeps at most three marks: placing a fourth
+/// removes the player's oldest mark, so the board never fills up and games
+/// can go on forever.
+///
+/// The oldest mark is removed once the new one is down, so it can't be placed
+/// on the square that is about to empty.  Marks are kept oldest first, and
+/// since the number of marks stops saying whose turn it is, that is tracked
+/// too.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DisappearingBoard {
+    board: Board,
+    x_marks: Vec<Move>,
+    o_marks: Vec<Move>,
+    active_player: Player,
+}
+
+impl DisappearingBoard {
+
+    pub fn empty() -> Self {
+        DisappearingBoard { board: Board::empty(), x_marks: Vec::new(), o_marks: Vec::new(), active_player: Player::X }
+    }
+
+    /// Replays `moves` from the empty board, X first, since a position alone
+    /// doesn't say which marks are oldest.
+    pub fn from_moves(moves: &[Move]) -> Result<Self, MoveError> {
+        let mut board = DisappearingBoard::empty();
+        for m in moves {
+            let player = board.get_active_player().ok_or(MoveError::GameOver)?;
+            board.make_move(player, *m)?;
+        }
+        Ok(board)
+    }
+
+    pub fn get_board(&self) -> Board {
+        self.board
+    }
+
+    /// `player`'s marks, oldest first.
+    pub fn get_marks(&self, player: Player) -> &[Move] {
+        match player {
+            Player::X => &self.x_marks,
+            Player::O => &self.o_marks,
+        }
+    }
+
+    pub fn get_outcome(&self) -> Outcome {
+        self.board.get_outcome()
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        match self.get_outcome() {
+            Outcome::InProgress => Some(self.active_player),
+            _ => None,
+        }
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<Move> {
+        match self.get_active_player() {
+            Some(_) => self.board.get_legal_moves(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn make_move(&mut self, player: Player, m: Move) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        self.board.place_mark(player, m)?;
+        let marks = match player {
+            Player::X => &mut self.x_marks,
+            Player::O => &mut self.o_marks,
+        };
+        marks.push(m);
+        if marks.len() > MAX_MARKS {
+            let oldest = marks.remove(0);
+            let shape = self.board.get_shape();
+            let (mut x_bitboard, mut o_bitboard) = (self.board.get_x_bitboard(), self.board.get_o_bitboard());
+            let square = shape.get_square(oldest.get_row(), oldest.get_column());
+            match player {
+                Player::X => x_bitboard.clear_square(square),
+                Player::O => o_bitboard.clear_square(square),
+            }
+            self.board = Board::new(x_bitboard, o_bitboard);
+        }
+        self.active_player = player.other();
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, player: Player, m: Move) -> Result<Self, MoveError> {
+        let mut new_board = self.clone();
+        new_board.make_move(player, m)?;
+        Ok(new_board)
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.board.to_string()
+    }
+
+    /// Describes an evaluation as a forced win, or as a draw in which neither
+    /// player can ever force a line.
+    pub fn evaluation_to_string(evaluation: &Evaluation) -> String {
+        match evaluation {
+            Evaluation::Win { player, n_plies } if *n_plies > 0 => format!("{} forces a win in {}", player.to_string(), n_plies),
+            Evaluation::Draw => "Drawn: neither player can force a line, so the game goes on forever".to_string(),
+            _ => evaluation.to_string(),
+        }
+    }
+
+}
+
+impl Game for DisappearingBoard {
+    type Move = Move;
+
+    fn get_outcome(&self) -> Outcome {
+        DisappearingBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        DisappearingBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<Move> {
+        DisappearingBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: Move) -> Self {
+        let player = DisappearingBoard::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+}
+
+
+#[cfg(test)]
+mod test_disappearing {
+    use super::*;
+    use crate::solver::retrograde::RetrogradeSolver;
+
+    #[test]
+    fn test_fourth_mark_removes_the_oldest() {
+        let moves = [Move::new(0, 0), Move::new(1, 1), Move::new(0, 1), Move::new(2, 2), Move::new(2, 0), Move::new(0, 2)];
+        let board = DisappearingBoard::from_moves(&moves).unwrap();
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert_eq!(board.get_legal_moves().len(), 3);
+        assert_eq!(board.with_move_made(Player::X, Move::new(0, 0)), Err(MoveError::SquareOccupied(Move::new(0, 0))));
+
+        let board = board.with_move_made(Player::X, Move::new(1, 0)).unwrap();
+        assert_eq!(board.get_marks(Player::X), &[Move::new(0, 1), Move::new(2, 0), Move::new(1, 0)]);
+        assert_eq!(board.get_board(), Board::from_position("_XO/XO_/X_O").unwrap());
+        assert_eq!(board.get_active_player(), Some(Player::O));
+
+        let board = board.with_move_made(Player::O, Move::new(0, 0)).unwrap();
+        assert_eq!(board.get_outcome(), Outcome::InProgress);
+        let board = board.with_move_made(Player::X, Move::new(2, 1)).unwrap();
+        assert_eq!(board.get_board(), Board::from_position("O_O/X__/XXO").unwrap());
+        assert_eq!(board.with_move_made(Player::X, Move::new(1, 2)), Err(MoveError::WrongTurn(Player::X)));
+        assert_eq!(DisappearingBoard::from_moves(&[Move::new(0, 0), Move::new(0, 0)]), Err(MoveError::SquareOccupied(Move::new(0, 0))));
+    }
+
+    #[test]
+    fn test_disappearing_marks_are_solved_despite_cycles() {
+        let (next_moves, evaluation) = RetrogradeSolver::from_board(DisappearingBoard::empty()).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(next_moves, vec![Move::new(0, 1), Move::new(1, 0), Move::new(1, 2), Move::new(2, 1)]);
+        assert_eq!(evaluation, Evaluation::win(Player::X, 13));
+        assert_eq!(DisappearingBoard::evaluation_to_string(&evaluation), "X forces a win in 13");
+
+        // Answering the centre with a corner holds forever.
+        let board = DisappearingBoard::from_moves(&[Move::new(1, 1), Move::new(0, 0)]).unwrap();
+        assert_eq!(RetrogradeSolver::from_board(board).get_evaluation(), Evaluation::draw());
+    }
+
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+use std::hash::Hash;
+
+use crate::board::board::{Outcome, Player};
+
+/// A two-player, perfect-information game as the generic solvers see it.
+/// Each variant implements this for its own position type; `Board` is
+/// ordinary tic-tac-toe.
+pub trait Game: Clone + Eq + Hash {
+    type Move: Copy + Eq + fmt::Debug;
+
+    fn get_outcome(&self) -> Outcome;
+
+    /// The player to move, or `None` once the game is over.
+    fn get_active_player(&self) -> Option<Player>;
+
+    fn get_legal_moves(&self) -> Vec<Self::Move>;
+
+    /// Plays `m`, which must be one of `get_legal_moves()`, for the active
+    /// player.
+    fn with_legal_move_made(&self, m: Self::Move) -> Self;
+
+    /// A guess at the value of a position in progress, positive when it
+    /// favours X, for searches that stop before the end of the game.
+    fn get_heuristic(&self) -> i32 {
+        0
+    }
+
+    /// A position with the same value as this one, that solvers cache results
+    /// under.  Games with symmetries can map equivalent positions to a single
+    /// key; by default every position is its own key.
+    fn get_transposition_key(&self) -> Self {
+        self.clone()
+    }
+}
@@ -0,0 +1,520 @@
+use std::sync::OnceLock;
+
+use crate::board::board::{Bitboard, Move, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::board::shape::BoardShape;
+use crate::board::symmetry::Symmetry;
+use crate::game::game::Game;
+
+const N_SQUARES: usize = 9;
+const N_PIECES_PER_SIZE: usize = 2;
+
+/// Heuristic weight of a line showing only one player's pieces, by how many
+/// it shows.
+const LINE_SCORES: [i32; 4] = [0, 1, 8, 64];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PieceSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl PieceSize {
+
+    pub fn all() -> [PieceSize; 3] {
+        [PieceSize::Small, PieceSize::Medium, PieceSize::Large]
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        match self {
+            PieceSize::Small => "small".to_string(),
+            PieceSize::Medium => "medium".to_string(),
+            PieceSize::Large => "large".to_string(),
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            PieceSize::Small => 'S',
+            PieceSize::Medium => 'M',
+            PieceSize::Large => 'L',
+        }
+    }
+
+    pub fn from_char(c: char) -> Result<PieceSize, ParseError> {
+        match c {
+            'S' => Ok(PieceSize::Small),
+            'M' => Ok(PieceSize::Medium),
+            'L' => Ok(PieceSize::Large),
+            _ => Err(ParseError::InvalidCharacter(c)),
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GobbletMove {
+    Place { size: PieceSize, to: Move },
+    Shift { from: Move, to: Move },
+}
+
+impl GobbletMove {
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        match self {
+            GobbletMove::Place { size, to } => format!("{} {}", size.to_char(), to.to_string()),
+            GobbletMove::Shift { from, to } => format!("{} -> {}", from.to_string(), to.to_string()),
+        }
+    }
+
+    /// Parses a piece size and the square to place it on, like `"L 1 1"`, or
+    /// a piece to move and where to, like `"0 0 -> 1 1"`.
+    pub fn from_string(s: &str) -> Result<GobbletMove, ParseError> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        let s = s.trim();
+        match s.split("->").collect::<Vec<&str>>().as_slice() {
+            [to] => {
+                let size = to.chars().next().ok_or_else(invalid).and_then(|c| PieceSize::from_char(c).map_err(|_| invalid()))?;
+                let to = Move::from_string(to[1..].trim()).map_err(|_| invalid())?;
+                Ok(GobbletMove::Place { size, to })
+            },
+            [from, to] => Ok(GobbletMove::Shift {
+                from: Move::from_string(from).map_err(|_| invalid())?,
+                to: Move::from_string(to).map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+}
+
+/// Gobblet Gobblers: each player has two small, two medium and two large
+/// pieces, and on their turn either places one on a square whose top piece is
+/// smaller, or moves one of their pieces already on top of a stack the same
+/// way.  Only the top piece of each stack counts, and three in a row on the
+/// lines of ordinary tic-tac-toe wins.
+///
+/// Moving a piece can uncover an opponent's line.  The line counts as soon as
+/// the piece is lifted, so unless the piece goes straight back onto that line
+/// the opponent wins, even if the move also completes a line of the mover's.
+///
+/// Pieces are kept as one bitboard per player and size.  The number of pieces
+/// on the board stops saying whose turn it is once pieces start moving, so
+/// that is tracked too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GobbletBoard {
+    x_pieces: [Bitboard; 3],
+    o_pieces: [Bitboard; 3],
+    active_player: Player,
+}
+
+impl GobbletBoard {
+
+    pub fn empty() -> Self {
+        GobbletBoard { x_pieces: [Bitboard::empty(); 3], o_pieces: [Bitboard::empty(); 3], active_player: Player::X }
+    }
+
+    /// Parses nine stacks separated by whitespace, with rows optionally
+    /// separated by `/`.  Each stack is `_` when empty, or its pieces from
+    /// the bottom up as a player and a size, like `"OSXL"` for a large X on a
+    /// small O.  X is to move when both players have as many pieces on the
+    /// board, and O when X has more, which `with_active_player` can change.
+    pub fn from_position(position: &str) -> Result<Self, ParseError> {
+        let stacks = position.split(|c: char| c == '/' || c.is_whitespace())
+            .filter(|stack| !stack.is_empty())
+            .collect::<Vec<&str>>();
+        if stacks.len() != N_SQUARES {
+            return Err(ParseError::InvalidPositionLength { position: position.to_string(), length: stacks.len() });
+        }
+        let mut board = GobbletBoard::empty();
+        for (square, stack) in stacks.iter().enumerate() {
+            if *stack == "_" {
+                continue;
+            }
+            let chars = stack.chars().collect::<Vec<char>>();
+            if chars.len() % 2 != 0 {
+                return Err(ParseError::InvalidStack(stack.to_string()));
+            }
+            let mut last_size = None;
+            for piece in chars.chunks(2) {
+                let player = match piece[0] {
+                    'X' => Player::X,
+                    'O' => Player::O,
+                    c => return Err(ParseError::InvalidCharacter(c)),
+                };
+                let size = PieceSize::from_char(piece[1])?;
+                if last_size.is_some_and(|last_size| last_size >= size) {
+                    return Err(ParseError::InvalidStack(stack.to_string()));
+                }
+                last_size = Some(size);
+                board.get_pieces_mut(player)[size.index()].set_square(square);
+            }
+        }
+        for player in [Player::X, Player::O] {
+            if board.get_pieces(player).iter().any(|pieces| pieces.n_set() > N_PIECES_PER_SIZE) {
+                return Err(ParseError::TooManyPieces(player));
+            }
+        }
+        let n_pieces = |pieces: &[Bitboard; 3]| pieces.iter().map(|pieces| pieces.n_set()).sum::<usize>();
+        if n_pieces(&board.x_pieces) > n_pieces(&board.o_pieces) {
+            board.active_player = Player::O;
+        }
+        Ok(board)
+    }
+
+    pub fn with_active_player(&self, active_player: Player) -> Self {
+        GobbletBoard { active_player, ..*self }
+    }
+
+    fn get_pieces(&self, player: Player) -> &[Bitboard; 3] {
+        match player {
+            Player::X => &self.x_pieces,
+            Player::O => &self.o_pieces,
+        }
+    }
+
+    fn get_pieces_mut(&mut self, player: Player) -> &mut [Bitboard; 3] {
+        match player {
+            Player::X => &mut self.x_pieces,
+            Player::O => &mut self.o_pieces,
+        }
+    }
+
+    /// How many pieces of `size` `player` hasn't placed yet.
+    pub fn get_n_in_reserve(&self, player: Player, size: PieceSize) -> usize {
+        N_PIECES_PER_SIZE - self.get_pieces(player)[size.index()].n_set()
+    }
+
+    /// The player and size of the piece on top of the stack at `m`, if any.
+    pub fn get_top_piece(&self, m: Move) -> Option<(Player, PieceSize)> {
+        let square = BoardShape::standard().get_square(m.get_row(), m.get_column());
+        self.get_top_piece_at(square)
+    }
+
+    fn get_top_piece_at(&self, square: usize) -> Option<(Player, PieceSize)> {
+        PieceSize::all().into_iter().rev().find_map(|size| {
+            if self.x_pieces[size.index()].is_square_set(square) {
+                Some((Player::X, size))
+            } else if self.o_pieces[size.index()].is_square_set(square) {
+                Some((Player::O, size))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The squares where `player`'s piece is on top.
+    pub fn get_visible(&self, player: Player) -> Bitboard {
+        let mut visible = Bitboard::empty();
+        let mut covered = Bitboard::empty();
+        for size in PieceSize::all().into_iter().rev() {
+            let pieces = self.get_pieces(player)[size.index()];
+            visible = visible.union(&pieces.difference(&covered));
+            covered = covered.union(&self.x_pieces[size.index()]).union(&self.o_pieces[size.index()]);
+        }
+        visible
+    }
+
+    /// The squares a piece of `size` can go on: empty ones, and ones whose
+    /// top piece is smaller.
+    fn get_coverable(&self, size: PieceSize) -> Bitboard {
+        let blocking = PieceSize::all().into_iter()
+            .filter(|other| *other >= size)
+            .fold(Bitboard::empty(), |blocking, other| blocking.union(&self.x_pieces[other.index()]).union(&self.o_pieces[other.index()]));
+        BoardShape::standard().get_full_bitboard().difference(&blocking)
+    }
+
+    /// The player to move wins if the last move uncovered one of their
+    /// lines, whether or not it also completed a line of the last mover's,
+    /// and loses if they have no move at all.
+    pub fn get_outcome(&self) -> Outcome {
+        let shape = BoardShape::standard();
+        let last_player = self.active_player.other();
+        if shape.is_victory(self.get_visible(self.active_player)) {
+            Outcome::Victory(self.active_player)
+        } else if shape.is_victory(self.get_visible(last_player)) || !self.has_moves() {
+            Outcome::Victory(last_player)
+        } else {
+            Outcome::InProgress
+        }
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        match self.get_outcome() {
+            Outcome::InProgress => Some(self.active_player),
+            _ => None,
+        }
+    }
+
+    /// Every placement and every move of a piece on top, with the moves that
+    /// complete a line first and then the ones leaving the best lines, so
+    /// that searches find wins and blocks early.
+    pub fn get_legal_moves(&self) -> Vec<GobbletMove> {
+        let player = match self.get_active_player() {
+            Some(player) => player,
+            None => return Vec::new(),
+        };
+        let shape = BoardShape::standard();
+        let mut moves = self.get_moves_and_boards().into_iter().map(|(m, board)| {
+            let is_win = shape.is_victory(board.get_visible(player)) && !shape.is_victory(board.get_visible(player.other()));
+            let heuristic = match player {
+                Player::X => board.get_heuristic(),
+                Player::O => -board.get_heuristic(),
+            };
+            (m, (!is_win, -heuristic))
+        }).collect::<Vec<(GobbletMove, (bool, i32))>>();
+        moves.sort_by_key(|(_, key)| *key);
+        moves.into_iter().map(|(m, _)| m).collect()
+    }
+
+    /// Whether the player to move has any move, which is only in doubt once
+    /// all of their pieces are placed.
+    fn has_moves(&self) -> bool {
+        let player = self.active_player;
+        if PieceSize::all().into_iter().any(|size| self.get_n_in_reserve(player, size) > 0) {
+            return true;
+        }
+        self.get_visible(player).get_squares().into_iter().any(|from| {
+            let (_, size) = self.get_top_piece_at(from).expect("Visible squares have a piece on top.");
+            let mut lifted = *self;
+            lifted.get_pieces_mut(player)[size.index()].clear_square(from);
+            lifted.get_coverable(size).get_squares().into_iter().any(|to| to != from)
+        })
+    }
+
+    /// The moves of the player to move, whether or not the game is over,
+    /// each with the board it leads to.
+    fn get_moves_and_boards(&self) -> Vec<(GobbletMove, GobbletBoard)> {
+        let shape = BoardShape::standard();
+        let player = self.active_player;
+        let mut moves = Vec::new();
+        for size in PieceSize::all().into_iter().rev() {
+            if self.get_n_in_reserve(player, size) > 0 {
+                for to in self.get_coverable(size).get_squares() {
+                    moves.push((GobbletMove::Place { size, to: shape.get_move(to) }, self.with_piece_put(player, size, to)));
+                }
+            }
+        }
+        for from in self.get_visible(player).get_squares() {
+            let (_, size) = self.get_top_piece_at(from).expect("Visible squares have a piece on top.");
+            let mut lifted = *self;
+            lifted.get_pieces_mut(player)[size.index()].clear_square(from);
+            for to in lifted.get_coverable(size).get_squares().into_iter().filter(|to| *to != from) {
+                moves.push((GobbletMove::Shift { from: shape.get_move(from), to: shape.get_move(to) }, lifted.with_piece_put(player, size, to)));
+            }
+        }
+        moves
+    }
+
+    /// Puts a piece on `square` and passes the turn, without checking that
+    /// the move is legal.
+    fn with_piece_put(&self, player: Player, size: PieceSize, square: usize) -> Self {
+        let mut board = *self;
+        board.get_pieces_mut(player)[size.index()].set_square(square);
+        board.active_player = player.other();
+        board
+    }
+
+    pub fn make_move(&mut self, player: Player, m: GobbletMove) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        // Pieces are lifted off a copy, so that an illegal move leaves the
+        // board as it was.
+        let shape = BoardShape::standard();
+        let mut board = *self;
+        let (size, to) = match m {
+            GobbletMove::Place { size, to } => {
+                if self.get_n_in_reserve(player, size) == 0 {
+                    return Err(MoveError::NoPieceLeft(size));
+                }
+                (size, to)
+            },
+            GobbletMove::Shift { from, to } => {
+                let size = match shape.contains(from).then(|| self.get_top_piece(from)).flatten() {
+                    Some((owner, size)) if owner == player => size,
+                    _ => return Err(MoveError::NotOwnPiece(from)),
+                };
+                if from == to {
+                    return Err(MoveError::CannotCover(to));
+                }
+                board.get_pieces_mut(player)[size.index()].clear_square(shape.get_square(from.get_row(), from.get_column()));
+                (size, to)
+            },
+        };
+        if !shape.contains(to) {
+            return Err(MoveError::OutOfBounds(to));
+        }
+        let square = shape.get_square(to.get_row(), to.get_column());
+        if !board.get_coverable(size).is_square_set(square) {
+            return Err(MoveError::CannotCover(to));
+        }
+        *self = board.with_piece_put(player, size, square);
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, player: Player, m: GobbletMove) -> Result<Self, MoveError> {
+        let mut new_board = *self;
+        new_board.make_move(player, m)?;
+        Ok(new_board)
+    }
+
+    /// Each stack from the bottom up, in the format `from_position` reads.
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        (0..N_SQUARES).collect::<Vec<usize>>().chunks(3)
+            .map(|row| row.iter().map(|square| {
+                let stack = PieceSize::all().into_iter().filter_map(|size| {
+                    if self.x_pieces[size.index()].is_square_set(*square) {
+                        Some(format!("X{}", size.to_char()))
+                    } else if self.o_pieces[size.index()].is_square_set(*square) {
+                        Some(format!("O{}", size.to_char()))
+                    } else {
+                        None
+                    }
+                }).collect::<String>();
+                match stack.is_empty() {
+                    true => "_".to_string(),
+                    false => stack,
+                }
+            }).collect::<Vec<String>>().join(" "))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Only symmetries in `Symmetry::all()` are meaningful here.
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        let index = Symmetry::all().iter().position(|other| *other == symmetry).expect("Every symmetry is in Symmetry::all().");
+        self.transformed_by_index(index)
+    }
+
+    /// Transposition keys transform the board every way at every searched
+    /// position, so each 3x3 bitboard's images are looked up rather than
+    /// worked out square by square.
+    fn transformed_by_index(&self, index: usize) -> Self {
+        static TRANSFORMED: OnceLock<Vec<Vec<Bitboard>>> = OnceLock::new();
+        let transformed = TRANSFORMED.get_or_init(|| {
+            let shape = BoardShape::standard();
+            Symmetry::all().iter()
+                .map(|symmetry| (0..1 << N_SQUARES).map(|bits| Bitboard::from_bits(bits).transformed(*symmetry, &shape)).collect())
+                .collect()
+        });
+        let transform = |pieces: Bitboard| transformed[index][pieces.to_bits() as usize];
+        GobbletBoard {
+            x_pieces: self.x_pieces.map(transform),
+            o_pieces: self.o_pieces.map(transform),
+            active_player: self.active_player,
+        }
+    }
+
+    /// Scores lines showing only one player's pieces, positive when they
+    /// favour X.
+    pub fn get_heuristic(&self) -> i32 {
+        let x_visible = self.get_visible(Player::X);
+        let o_visible = self.get_visible(Player::O);
+        let mut score = 0;
+        for mask in BoardShape::standard().get_win_masks() {
+            let n_x = x_visible.intersection(mask).n_set();
+            let n_o = o_visible.intersection(mask).n_set();
+            if n_o == 0 {
+                score += LINE_SCORES[n_x];
+            }
+            if n_x == 0 {
+                score -= LINE_SCORES[n_o];
+            }
+        }
+        score
+    }
+
+}
+
+impl Game for GobbletBoard {
+    type Move = GobbletMove;
+
+    fn get_outcome(&self) -> Outcome {
+        GobbletBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        GobbletBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<GobbletMove> {
+        GobbletBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: GobbletMove) -> Self {
+        let player = GobbletBoard::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    fn get_heuristic(&self) -> i32 {
+        GobbletBoard::get_heuristic(self)
+    }
+
+    /// Rotations and reflections of the board keep every line a line, so
+    /// positions are keyed by their smallest image.
+    fn get_transposition_key(&self) -> Self {
+        (0..Symmetry::all().len())
+            .map(|index| self.transformed_by_index(index))
+            .min_by_key(|board| (board.x_pieces, board.o_pieces))
+            .expect("There is always at least the identity symmetry.")
+    }
+}
+
+
+#[cfg(test)]
+mod test_gobblet {
+    use super::*;
+    use crate::solver::evaluation::Evaluation;
+    use crate::solver::iterative_deepening::IterativeDeepeningSolver;
+
+    #[test]
+    fn test_larger_pieces_cover_smaller_ones() {
+        let board = GobbletBoard::from_position("XL XSOM XM / _ _ _ / _ _ _").unwrap();
+        assert_eq!(board.get_active_player(), Some(Player::O));
+        assert_eq!(board.get_top_piece(Move::new(0, 1)), Some((Player::O, PieceSize::Medium)));
+        assert_eq!(board.get_n_in_reserve(Player::X, PieceSize::Small), 1);
+        assert_eq!(GobbletBoard::from_position(&board.to_string()), Ok(board));
+
+        let place = |size, row, col| GobbletMove::Place { size, to: Move::new(row, col) };
+        let shift = |from: (usize, usize), to: (usize, usize)| GobbletMove::Shift { from: Move::new(from.0, from.1), to: Move::new(to.0, to.1) };
+        assert_eq!(board.with_move_made(Player::O, place(PieceSize::Medium, 0, 2)), Err(MoveError::CannotCover(Move::new(0, 2))));
+        assert_eq!(board.with_move_made(Player::O, shift((0, 0), (1, 1))), Err(MoveError::NotOwnPiece(Move::new(0, 0))));
+        assert_eq!(board.with_move_made(Player::O, shift((0, 1), (0, 1))), Err(MoveError::CannotCover(Move::new(0, 1))));
+        let covered = board.with_move_made(Player::O, place(PieceSize::Large, 0, 2)).unwrap();
+        assert_eq!(covered.get_top_piece(Move::new(0, 2)), Some((Player::O, PieceSize::Large)));
+        let no_large_left = GobbletBoard::from_position("XL XSOM XM / OL _ _ / _ _ OL").unwrap().with_active_player(Player::O);
+        assert_eq!(no_large_left.with_move_made(Player::O, place(PieceSize::Large, 1, 1)), Err(MoveError::NoPieceLeft(PieceSize::Large)));
+
+        // Lifting the medium piece uncovers X's line, wherever it goes.
+        assert_eq!(board.with_move_made(Player::O, shift((0, 1), (1, 1))).unwrap().get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(GobbletMove::from_string("0 1 -> 1 1"), Ok(shift((0, 1), (1, 1))));
+        assert_eq!(GobbletMove::from_string("L 2 2"), Ok(place(PieceSize::Large, 2, 2)));
+
+        assert_eq!(GobbletBoard::from_position("OMOS _ _ / _ _ _ / _ _ _"), Err(ParseError::InvalidStack("OMOS".to_string())));
+        assert_eq!(GobbletBoard::from_position("XS XS XS / _ _ _ / _ _ _"), Err(ParseError::TooManyPieces(Player::X)));
+    }
+
+    #[test]
+    fn test_first_player_wins_gobblet_gobblers() {
+        let (next_moves, evaluation) = IterativeDeepeningSolver::from_board(GobbletBoard::empty(), 13).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(evaluation, Evaluation::win(Player::X, 13));
+        let mut next_moves = next_moves.iter().map(|m| m.to_string()).collect::<Vec<String>>();
+        next_moves.sort();
+        assert_eq!(next_moves, vec![
+            "L (0, 0)", "L (0, 2)", "L (1, 1)", "L (2, 0)", "L (2, 2)",
+            "S (0, 1)", "S (1, 0)", "S (1, 2)", "S (2, 1)",
+        ]);
+    }
+
+}
@@ -0,0 +1,59 @@
+//! A solver for tic-tac-toe and its m,n,k generalizations.
+//!
+//! Positions are `Board`s built from strings like `"XO_ _X_ ___"` (any
+//! `BoardShape` up to 64 squares, with any win length), moves are
+//! `Move`s, and solvers return an exact `Evaluation` along with the best moves
+//! or an optimal line:
+//!
+//! * `AlphaBetaSolver` searches the position directly and answers instantly.
+//! * `Solver` builds the full game `Tree` below the position first.
+//! * `Tablebase` looks every reachable 3x3 position up in a precomputed table.
+//!
+//! Variants such as `Notakto` and 3D `CubeBoard`s implement the `Game`
+//! trait, which `AlphaBetaSolver` can search just like a `Board`.  Games too
+//! large to solve, like `UltimateBoard` or 4x4x4 Qubic, are searched to a
+//! fixed depth by `DepthLimitedSolver`.
+
+pub mod board;
+pub mod game;
+pub mod tree;
+pub mod solver;
+pub mod notakto;
+pub mod ultimate;
+pub mod cube;
+pub mod wild;
+pub mod order_and_chaos;
+pub mod numerical;
+pub mod morris;
+pub mod disappearing;
+pub mod gobblet;
+pub mod quantum;
+
+pub use crate::board::board::{Bitboard, Board, Move, Outcome, Player};
+pub use crate::board::error::{IndexError, MoveError, ParseError, ShapeError};
+pub use crate::board::rules::Rules;
+pub use crate::board::shape::BoardShape;
+pub use crate::board::symmetry::Symmetry;
+pub use crate::board::validation::Illegality;
+pub use crate::cube::cube::{CubeBoard, CubeMove};
+pub use crate::disappearing::disappearing::DisappearingBoard;
+pub use crate::game::game::Game;
+pub use crate::gobblet::gobblet::{GobbletBoard, GobbletMove, PieceSize};
+pub use crate::morris::morris::{MorrisBoard, MorrisMove};
+pub use crate::notakto::notakto::{Notakto, NotaktoMove};
+pub use crate::numerical::numerical::{NumericalBoard, NumericalMove};
+pub use crate::order_and_chaos::order_and_chaos::OrderAndChaos;
+pub use crate::quantum::quantum::{QuantumBoard, QuantumMove};
+pub use crate::solver::alpha_beta::AlphaBetaSolver;
+pub use crate::solver::depth_limited::DepthLimitedSolver;
+pub use crate::solver::error::{SolverError, TablebaseError};
+pub use crate::solver::evaluation::Evaluation;
+pub use crate::solver::expectimax::ExpectimaxSolver;
+pub use crate::solver::iterative_deepening::IterativeDeepeningSolver;
+pub use crate::solver::probability::{Probability, WinProbabilities};
+pub use crate::solver::retrograde::RetrogradeSolver;
+pub use crate::solver::solver::Solver;
+pub use crate::solver::tablebase::{Tablebase, TablebaseEntry};
+pub use crate::tree::tree::Tree;
+pub use crate::ultimate::ultimate::{UltimateBoard, UltimateMove};
+pub use crate::wild::wild::{WildBoard, WildMove};
@@ -1,11 +1,73 @@
-mod board;
-mod tree;
-mod solver;
 
-use clap::{App, SubCommand, Arg};
-use crate::board::board::{Board, Move, Outcome};
-use crate::solver::solver::Solver;
+use std::fmt;
+use std::process;
 
+use clap::{App, ArgMatches, SubCommand, Arg};
+use tic_tac_toe_solver::{
+    AlphaBetaSolver, Board, CubeBoard, DepthLimitedSolver, DisappearingBoard, ExpectimaxSolver, GobbletBoard,
+    Illegality, IterativeDeepeningSolver, MorrisBoard, Move, Notakto, NumericalBoard, OrderAndChaos, ParseError, Player,
+    QuantumBoard, QuantumMove, RetrogradeSolver, Rules, Solver, SolverError, Tablebase, TablebaseError, UltimateBoard,
+    WildBoard,
+};
+
+
+/// Everything that can stop the CLI, each with its own exit code so scripts
+/// can tell a typo in the position from a position that is already decided.
+#[derive(Debug)]
+enum CliError {
+    InvalidCommand,
+    MissingPosition,
+    Parse(ParseError),
+    IllegalPosition(Illegality),
+    Solver(SolverError),
+}
+
+impl CliError {
+
+    fn get_exit_code(&self) -> i32 {
+        match self {
+            CliError::InvalidCommand | CliError::MissingPosition => 2,
+            CliError::Parse(_) => 3,
+            CliError::IllegalPosition(_) | CliError::Solver(SolverError::IllegalPosition(_)) => 4,
+            CliError::Solver(SolverError::GameOver) | CliError::Solver(SolverError::Move(_)) => 5,
+            CliError::Solver(SolverError::Tablebase(_))
+                | CliError::Solver(SolverError::UnsupportedShape(_))
+                | CliError::Solver(SolverError::UnsupportedRules(_))
+                | CliError::Solver(SolverError::UnsupportedBlockedSquares) => 6,
+        }
+    }
+
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::InvalidCommand => write!(f, "Invalid command!"),
+            CliError::MissingPosition => write!(f, "Needs a Position!"),
+            CliError::Parse(error) => write!(f, "{}", error),
+            CliError::IllegalPosition(illegality) => write!(f, "This position can't arise in a real game. {}.", illegality),
+            CliError::Solver(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseError> for CliError {
+    fn from(error: ParseError) -> Self {
+        CliError::Parse(error)
+    }
+}
+
+impl From<SolverError> for CliError {
+    fn from(error: SolverError) -> Self {
+        CliError::Solver(error)
+    }
+}
+
+impl From<TablebaseError> for CliError {
+    fn from(error: TablebaseError) -> Self {
+        CliError::Solver(SolverError::Tablebase(error))
+    }
+}
 
 
 fn main() {
@@ -16,151 +78,557 @@ fn main() {
 				.about("Solve Tic Tac Toe Position")
 				.arg(
 					Arg::with_name("Position")
-						.help("Tic Tac Toe Position")						
+						.help("Tic Tac Toe Position, with rows separated by '/' or whitespace for boards other than 3x3 and '#' for blocked squares")						
 				).arg(
+                    Arg::with_name("Win Length")
+                        .help("Number of marks in a row needed to win [default: 3]")
+                        .short('k')
+                        .long("win-length")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Misere")
+                        .help("Play misère: the player who completes a line loses")
+                        .long("misere")
+                ).arg(
+                    Arg::with_name("Gravity")
+                        .help("Play with gravity: marks fall to the lowest empty square of their column, as in Connect Four")
+                        .long("gravity")
+                ).arg(
                     Arg::with_name("Show Line")
                         .help("Show an example optimal line from the best move")
                         .short('l')
                         .long("line")
+                ).arg(
+                    Arg::with_name("Tree")
+                        .help("Solve by building the full game tree instead of using alpha-beta search")
+                        .short('t')
+                        .long("tree")
+                ).arg(
+                    Arg::with_name("Group Symmetric")
+                        .help("Group best moves that are equivalent under rotation or reflection of the board")
+                        .short('g')
+                        .long("group-symmetric")
+                ).arg(
+                    Arg::with_name("Tablebase")
+                        .help("Answer by looking the position up in a tablebase file instead of searching")
+                        .long("tablebase")
+                        .takes_value(true)
+                ).arg(
+                    Arg::with_name("Depth")
+                        .help("Only look for wins within this many plies, scoring positions still open there by their lines, for boards too big to solve exactly such as Connect Four [default: solve exactly]")
+                        .short('d')
+                        .long("depth")
+                        .takes_value(true)
+                        .conflicts_with_all(&["Tree", "Tablebase"])
+                ).arg(
+                    Arg::with_name("Allow Illegal")
+                        .help("Evaluate positions that can't arise in a real game, with a warning, instead of refusing")
+                        .long("allow-illegal")
                 )
+            ).subcommand(
+                SubCommand::with_name("notakto")
+                    .about("Solve Notakto, where both players play X on several boards and whoever finishes the last board loses")
+                    .arg(
+                        Arg::with_name("Boards")
+                            .help("Starting boards, containing only X's")
+                            .required(true)
+                            .multiple(true)
+                    )
+            ).subcommand(
+                SubCommand::with_name("wild")
+                    .about("Solve Wild tic-tac-toe, where each player may place either symbol and any line wins")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("The board, with rows separated by '/' or whitespace")
+                            .required(true)
+                    ).arg(
+                        Arg::with_name("Misere")
+                            .help("Play misère: the player who completes a line loses")
+                            .long("misere")
+                    )
+            ).subcommand(
+                SubCommand::with_name("numerical")
+                    .about("Solve numerical tic-tac-toe, where players write odd and even numbers and lines adding up to 15 win")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("The 3x3 board of digits 1-9 and '_' for empty squares, with rows optionally separated by '/' or whitespace")
+                            .required(true)
+                    )
+            ).subcommand(
+                SubCommand::with_name("order-and-chaos")
+                    .about("Search an Order and Chaos position to a fixed depth")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("The 6x6 board, with rows separated by '/' or whitespace")
+                            .required(true)
+                    ).arg(
+                        Arg::with_name("Depth")
+                            .help("Number of plies to search before scoring positions heuristically")
+                            .short('d')
+                            .long("depth")
+                            .takes_value(true)
+                            .default_value("4")
+                    )
+            ).subcommand(
+                SubCommand::with_name("ultimate")
+                    .about("Search an Ultimate tic-tac-toe position to a fixed depth")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("The 9x9 grid, with rows separated by '/' or whitespace")
+                            .required(true)
+                    ).arg(
+                        Arg::with_name("Board")
+                            .help("Local board (0-8, in reading order) the player to move has been sent to [default: any]")
+                            .short('b')
+                            .long("board")
+                            .takes_value(true)
+                    ).arg(
+                        Arg::with_name("Depth")
+                            .help("Number of plies to search before scoring positions heuristically")
+                            .short('d')
+                            .long("depth")
+                            .takes_value(true)
+                            .default_value("6")
+                    ).arg(
+                        Arg::with_name("Show Line")
+                            .help("Show the principal variation")
+                            .short('l')
+                            .long("line")
+                    )
+            ).subcommand(
+                SubCommand::with_name("cube")
+                    .about("Solve a 3x3x3 cube, or search a 4x4x4 (Qubic) cube to a fixed depth")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("All 27 or 64 squares, layer by layer, with rows and layers separated by '/' or whitespace")
+                            .required(true)
+                    ).arg(
+                        Arg::with_name("Depth")
+                            .help("Number of plies to search a 4x4x4 cube before scoring positions heuristically")
+                            .short('d')
+                            .long("depth")
+                            .takes_value(true)
+                            .default_value("4")
+                    )
+            ).subcommand(
+                SubCommand::with_name("morris")
+                    .about("Solve Three Men's Morris or Achi, where pieces are placed and then slid to adjacent squares")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("The board, with rows separated by '/' or whitespace")
+                            .required(true)
+                    ).arg(
+                        Arg::with_name("Achi")
+                            .help("Play Achi, with four pieces each instead of three")
+                            .long("achi")
+                    ).arg(
+                        Arg::with_name("Player")
+                            .help("Player to move once all pieces are placed [default: X]")
+                            .short('p')
+                            .long("player")
+                            .takes_value(true)
+                            .possible_values(["X", "O"])
+                    )
+            ).subcommand(
+                SubCommand::with_name("disappearing")
+                    .about("Solve disappearing-marks tic-tac-toe, where placing a fourth mark removes your oldest one")
+                    .arg(
+                        Arg::with_name("Moves")
+                            .help("Moves played so far, X first, like '1 1' or '11' [default: none]")
+                            .multiple(true)
+                    )
+            ).subcommand(
+                SubCommand::with_name("gobblet")
+                    .about("Look for a forced win in Gobblet Gobblers, where larger pieces cover smaller ones and pieces can be moved")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("Nine stacks separated by whitespace, each '_' or its pieces from the bottom up like 'OSXL', with rows optionally separated by '/'")
+                            .required(true)
+                    ).arg(
+                        Arg::with_name("Player")
+                            .help("Player to move [default: X, or O when X has more pieces on the board]")
+                            .short('p')
+                            .long("player")
+                            .takes_value(true)
+                            .possible_values(["X", "O"])
+                    ).arg(
+                        Arg::with_name("Depth")
+                            .help("Longest forced win to look for, in plies")
+                            .short('d')
+                            .long("depth")
+                            .takes_value(true)
+                            .default_value("13")
+                    )
+            ).subcommand(
+                SubCommand::with_name("quantum")
+                    .about("Solve quantum tic-tac-toe, where marks are placed in two squares at once until cycles collapse them")
+                    .arg(
+                        Arg::with_name("Moves")
+                            .help("Moves played so far, X first: spooky marks like '0 0 & 1 1', collapses like 'collapse to 1 1', and a final classical mark like '2 2'")
+                            .multiple(true)
+                    ).arg(
+                        Arg::with_name("Depth")
+                            .help("Only look for wins within this many plies, for positions with too few marks to solve exactly in reasonable time [default: solve exactly]")
+                            .short('d')
+                            .long("depth")
+                            .takes_value(true)
+                    )
+            ).subcommand(
+                SubCommand::with_name("random-turn")
+                    .about("Solve random-turn tic-tac-toe, where a coin toss decides who moves each turn, giving exact win probabilities")
+                    .arg(
+                        Arg::with_name("Position")
+                            .help("Tic Tac Toe Position, with any number of marks for each player")
+                            .default_value("___/___/___")
+                    ).arg(
+                        Arg::with_name("Player")
+                            .help("Only show the moves for this player winning the toss [default: both]")
+                            .short('p')
+                            .long("player")
+                            .takes_value(true)
+                            .possible_values(["X", "O"])
+                    )
+            ).subcommand(
+                SubCommand::with_name("generate-tablebase")
+                    .about("Solve every reachable position and write the results to a tablebase file")
+                    .arg(
+                        Arg::with_name("Path")
+                            .help("File to write the tablebase to")
+                            .required(true)
+                    )
             ).get_matches();
-    
-    if let Some(matches) = matches.subcommand_matches("solve") {
-        match matches.value_of("Position") {
-            Some(position) => {
-                match Board::from_position(position) {
-                    Ok(board) => {
-                        let mut solver = Solver::from_board(board);
-                        match matches.is_present("Show Line") {
-                            true => {
-                                match solver.get_evaluation_and_line() {
-                                    (evaluation, line) => {
-                                        let mut moves_and_boards: Vec<(Option<Move>, Board)> = vec![(None, board)];
-                                        for m in line.iter() {
-                                            let last_board = moves_and_boards.last().unwrap().1;
-                                            let next_board = last_board.with_move_made(
-                                                last_board.get_active_player().unwrap(),
-                                                *m
-                                            ).unwrap();
-                                            moves_and_boards.push((Some(*m), next_board));
-                                        }
-                                        let boards_string = moves_and_boards.iter().map(|(maybe_m, board)| {
-                                            match maybe_m {
-                                                Some(m) => {
-                                                    format!("{}", board.to_string_with_square_highlighted(m.get_row(), m.get_column()))
-                                                },
-                                                None => {
-                                                    format!("{}", board.to_string())
-                                                }
-                                            }
-                                        }).collect::<Vec<String>>().join("\n\n");
-                                        println!("\n\nEvaluation:\n{}\n\nLine:\n{}", evaluation.to_string(), boards_string);
-                                    },
-                                }
-                            },
-                            false => {
-                                match solver.get_next_moves_and_evaluation() {
-                                    Ok((next_moves, evaluation)) => {
-                                        let next_moves_string = next_moves.iter()
-                                            .map(|x| x.to_string())
-                                            .collect::<Vec<String>>()
-                                            .join("\n");
-                                        
-                                        println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation.to_string(), next_moves_string);
-                                    },
-                                    Err(error) => {
-                                        println!("{}", error);
-                                    }
-                                }
-                            }
-                        }
+
+    if let Err(error) = run(&matches) {
+        eprintln!("{}", error);
+        process::exit(error.get_exit_code());
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), CliError> {
+    if let Some(matches) = matches.subcommand_matches("generate-tablebase") {
+        let path = matches.value_of("Path").expect("Path is a required argument.");
+        let tablebase = Tablebase::generate();
+        tablebase.save(path)?;
+        println!("Wrote {} positions to {}", tablebase.len(), path);
+        Ok(())
+    } else if let Some(matches) = matches.subcommand_matches("solve") {
+        solve(matches)
+    } else if let Some(matches) = matches.subcommand_matches("notakto") {
+        solve_notakto(matches)
+    } else if let Some(matches) = matches.subcommand_matches("wild") {
+        solve_wild(matches)
+    } else if let Some(matches) = matches.subcommand_matches("numerical") {
+        solve_numerical(matches)
+    } else if let Some(matches) = matches.subcommand_matches("order-and-chaos") {
+        solve_order_and_chaos(matches)
+    } else if let Some(matches) = matches.subcommand_matches("ultimate") {
+        solve_ultimate(matches)
+    } else if let Some(matches) = matches.subcommand_matches("cube") {
+        solve_cube(matches)
+    } else if let Some(matches) = matches.subcommand_matches("morris") {
+        solve_morris(matches)
+    } else if let Some(matches) = matches.subcommand_matches("disappearing") {
+        solve_disappearing(matches)
+    } else if let Some(matches) = matches.subcommand_matches("gobblet") {
+        solve_gobblet(matches)
+    } else if let Some(matches) = matches.subcommand_matches("quantum") {
+        solve_quantum(matches)
+    } else if let Some(matches) = matches.subcommand_matches("random-turn") {
+        solve_random_turn(matches)
+    } else {
+        Err(CliError::InvalidCommand)
+    }
+}
+
+fn solve(matches: &ArgMatches) -> Result<(), CliError> {
+    let tablebase = match matches.value_of("Tablebase") {
+        Some(path) => Some(Tablebase::load(path)?),
+        None => None,
+    };
+    let position = matches.value_of("Position").ok_or(CliError::MissingPosition)?;
+    let board = match matches.value_of("Win Length") {
+        Some(win_length) => {
+            let win_length = win_length.parse::<usize>().map_err(|_| ParseError::InvalidWinLength(win_length.to_string()))?;
+            Board::from_position_with_win_length(position, win_length)?
+        },
+        None => Board::from_position(position)?,
+    };
+    let rules = Rules::standard()
+        .with_misere(matches.is_present("Misere"))
+        .with_gravity(matches.is_present("Gravity"));
+    let board = board.with_rules(rules);
+    if let Err(illegality) = board.validate() {
+        match matches.is_present("Allow Illegal") {
+            true => eprintln!("Warning: this position can't arise in a real game. {}.", illegality),
+            false => return Err(CliError::IllegalPosition(illegality)),
+        }
+    }
+
+    let use_tree = matches.is_present("Tree");
+    let depth = match matches.value_of("Depth") {
+        Some(depth) => Some(depth.parse::<usize>().map_err(|_| ParseError::InvalidNumber(depth.to_string()))?),
+        None => None,
+    };
+    match matches.is_present("Show Line") {
+        true => {
+            let (evaluation, line) = match (&tablebase, use_tree, depth) {
+                (Some(tablebase), _, _) => tablebase.get_evaluation_and_line(&board)?,
+                (None, true, _) => Solver::from_board_with_symmetries(board).get_evaluation_and_line(),
+                (None, false, Some(depth)) => DepthLimitedSolver::from_board(board, depth).get_evaluation_and_line(),
+                (None, false, None) => AlphaBetaSolver::from_board(board).get_evaluation_and_line(),
+            };
+            let mut moves_and_boards: Vec<(Option<Move>, Board)> = vec![(None, board)];
+            for m in line.iter() {
+                let last_board = moves_and_boards.last().unwrap().1;
+                let next_board = last_board.with_move_made(
+                    last_board.get_active_player().unwrap(),
+                    *m
+                ).map_err(SolverError::Move)?;
+                moves_and_boards.push((Some(*m), next_board));
+            }
+            let boards_string = moves_and_boards.iter().map(|(maybe_m, board)| {
+                match maybe_m {
+                    Some(m) => {
+                        board.to_string_with_square_highlighted(m.get_row(), m.get_column())
                     },
-                    Err(error) => {
-                        println!("{}", error);
+                    None => {
+                        board.to_string()
                     }
                 }
-            },
-            None => {
-                println!("Needs a Position!");
-            }
+            }).collect::<Vec<String>>().join("\n\n");
+            println!("\n\nEvaluation:\n{}\n\nLine:\n{}", evaluation, boards_string);
+        },
+        false => {
+            let (next_moves, evaluation) = match (&tablebase, use_tree, depth) {
+                (Some(tablebase), _, _) => tablebase.get_next_moves_and_evaluation(&board)?,
+                (None, true, _) => Solver::from_board_with_symmetries(board).get_next_moves_and_evaluation()?,
+                (None, false, Some(depth)) => DepthLimitedSolver::from_board(board, depth).get_next_moves_and_evaluation()?,
+                (None, false, None) => AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation()?,
+            };
+            let describe_move = |m: &Move| match rules.has_gravity() {
+                true => m.to_column_string(),
+                false => m.to_string(),
+            };
+            let next_moves_string = match matches.is_present("Group Symmetric") {
+                true => board.group_equivalent_moves(&next_moves).iter()
+                    .map(|group| group.iter().map(describe_move).collect::<Vec<String>>().join(" ~ "))
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+                false => next_moves.iter()
+                    .map(describe_move)
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            };
+            println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation, next_moves_string);
         }
-    } else {
-        println!("Invalid command!");
     }
+    Ok(())
+}
+
+fn solve_notakto(matches: &ArgMatches) -> Result<(), CliError> {
+    let positions = matches.values_of("Boards").expect("Boards is a required argument.").collect::<Vec<&str>>();
+    let game = Notakto::from_positions(&positions)?;
+    let (next_moves, evaluation) = AlphaBetaSolver::from_board(game).get_next_moves_and_evaluation()?;
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", Notakto::evaluation_to_string(&evaluation), next_moves_string);
+    Ok(())
+}
+
+fn solve_wild(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position is a required argument.");
+    let board = Board::from_position(position)?;
+    let board = match matches.is_present("Misere") {
+        true => board.with_rules(Rules::misere()),
+        false => board,
+    };
+    let (next_moves, evaluation) = AlphaBetaSolver::from_board(WildBoard::new(board)).get_next_moves_and_evaluation()?;
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation.to_string_by_turn_order(), next_moves_string);
+    Ok(())
+}
+
+fn solve_numerical(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position is a required argument.");
+    let board = NumericalBoard::from_position(position)?;
+    let (next_moves, evaluation) = AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation()?;
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation.to_string_with_player_names("Odd player", "Even player"), next_moves_string);
+    Ok(())
+}
+
+fn solve_order_and_chaos(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position is a required argument.");
+    let depth = matches.value_of("Depth").expect("Depth has a default value.");
+    let depth = depth.parse::<usize>().map_err(|_| ParseError::InvalidNumber(depth.to_string()))?;
+    let game = OrderAndChaos::from_position(position)?;
+
+    let (next_moves, evaluation) = DepthLimitedSolver::from_board(game, depth).get_next_moves_and_evaluation()?;
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", OrderAndChaos::evaluation_to_string(&evaluation), next_moves_string);
+    Ok(())
 }
 
-#[cfg(test)]
-mod test_integration_tests {
-    use crate::solver::solver::Evaluation;
-
-    use super::*;
-
-    #[test]
-    fn test_solver_solves_tic_tac_toe() {
-        let mut solver = Solver::from_board(
-            Board::from_position(
-                "XOX
-                O_O
-                XOX",
-            ).unwrap()
-        );
-        assert_eq!(solver.get_next_moves(), Ok(vec![Move::new(1, 1)]));
-
-        let mut solver = Solver::from_board(
-            Board::from_position(
-                "X_X
-                O_O
-                ___",
-            ).unwrap()
-        );
-        assert_eq!(solver.get_next_moves(), Ok(vec![Move::new(0, 1), Move::new(1, 1)]));
-
-        let mut solver = Solver::from_board(
-            Board::from_position(
-                "XO_
-                _X_
-                ___",
-            ).unwrap()
-        );
-        assert_eq!(solver.get_next_moves().unwrap().len(), 6);
-        
-        let mut solver = Solver::from_board(
-            Board::from_position(
-                "XO_
-                _X_
-                __O",
-            ).unwrap()
-        );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(2, 0)], Evaluation::new(1.))));
-
-        let mut solver = Solver::from_board(
-            Board::from_position(
-                "XO_
-                ___
-                ___",
-            ).unwrap()
-        );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(1, 1), Move::new(2, 0)], Evaluation::new(1.))));
-
-        
-        let mut solver = Solver::from_board(
-            Board::from_position(
-                "XOX
-                _O_
-                __X",
-            ).unwrap()
-        );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 2), Move::new(2, 1)], Evaluation::new(-1.))));
-
-        let mut solver = Solver::from_board(
-            Board::from_position(
-                "XO_
-                O__
-                XXO",
-            ).unwrap()
-        );
-        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(0, 2), Move::new(1, 1), Move::new(1, 2)], Evaluation::new(0.))));
+fn solve_ultimate(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position is a required argument.");
+    let next_board = match matches.value_of("Board") {
+        Some(board) => match board.parse::<usize>() {
+            Ok(board) if board < 9 => Some(board),
+            _ => return Err(CliError::Parse(ParseError::InvalidNumber(board.to_string()))),
+        },
+        None => None,
+    };
+    let depth = matches.value_of("Depth").expect("Depth has a default value.");
+    let depth = depth.parse::<usize>().map_err(|_| ParseError::InvalidNumber(depth.to_string()))?;
+    let board = UltimateBoard::from_position(position)?.with_next_board(next_board);
 
+    let mut solver = DepthLimitedSolver::from_board(board, depth);
+    match matches.is_present("Show Line") {
+        true => {
+            let (evaluation, line) = solver.get_evaluation_and_line();
+            let line_string = line.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join("\n");
+            println!("\n\nEvaluation: {}\n\nLine:\n{}", evaluation, line_string);
+        },
+        false => {
+            let (next_moves, evaluation) = solver.get_next_moves_and_evaluation()?;
+            let next_moves_string = next_moves.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join("\n");
+            println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation, next_moves_string);
+        }
     }
+    Ok(())
+}
+
+fn solve_cube(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position is a required argument.");
+    let depth = matches.value_of("Depth").expect("Depth has a default value.");
+    let depth = depth.parse::<usize>().map_err(|_| ParseError::InvalidNumber(depth.to_string()))?;
+    let board = CubeBoard::from_position(position)?;
+
+    let (next_moves, evaluation) = match board.get_size() {
+        3 => AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation()?,
+        _ => DepthLimitedSolver::from_board(board, depth).get_next_moves_and_evaluation()?,
+    };
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation, next_moves_string);
+    Ok(())
+}
+
+fn solve_morris(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position is a required argument.");
+    let n_pieces = match matches.is_present("Achi") {
+        true => 4,
+        false => 3,
+    };
+    let board = MorrisBoard::from_position(position, n_pieces)?;
+    let board = match matches.value_of("Player") {
+        Some("O") if !board.is_placing(Player::O) => board.with_active_player(Player::O),
+        _ => board,
+    };
+
+    let (next_moves, evaluation) = RetrogradeSolver::from_board(board).get_next_moves_and_evaluation()?;
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation, next_moves_string);
+    Ok(())
+}
+
+fn solve_disappearing(matches: &ArgMatches) -> Result<(), CliError> {
+    let moves = match matches.values_of("Moves") {
+        Some(moves) => moves.map(Move::from_string).collect::<Result<Vec<Move>, ParseError>>()?,
+        None => Vec::new(),
+    };
+    let board = DisappearingBoard::from_moves(&moves).map_err(SolverError::Move)?;
+
+    let (next_moves, evaluation) = RetrogradeSolver::from_board(board.clone()).get_next_moves_and_evaluation()?;
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("{}\n\nEvaluation: {}\nIndifferent between these moves:\n{}", board.to_string(), DisappearingBoard::evaluation_to_string(&evaluation), next_moves_string);
+    Ok(())
+}
+
+fn solve_gobblet(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position is a required argument.");
+    let depth = matches.value_of("Depth").expect("Depth has a default value.");
+    let depth = depth.parse::<usize>().map_err(|_| ParseError::InvalidNumber(depth.to_string()))?;
+    let board = GobbletBoard::from_position(position)?;
+    let board = match matches.value_of("Player") {
+        Some("O") => board.with_active_player(Player::O),
+        Some(_) => board.with_active_player(Player::X),
+        None => board,
+    };
 
-}
\ No newline at end of file
+    let (next_moves, evaluation) = IterativeDeepeningSolver::from_board(board, depth).get_next_moves_and_evaluation()?;
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("\n\nEvaluation: {}\nIndifferent between these moves:\n{}", evaluation, next_moves_string);
+    Ok(())
+}
+
+fn solve_quantum(matches: &ArgMatches) -> Result<(), CliError> {
+    let moves = match matches.values_of("Moves") {
+        Some(moves) => moves.map(QuantumMove::from_string).collect::<Result<Vec<QuantumMove>, ParseError>>()?,
+        None => Vec::new(),
+    };
+    let board = QuantumBoard::from_moves(&moves).map_err(SolverError::Move)?;
+
+    let (next_moves, evaluation) = match matches.value_of("Depth") {
+        Some(depth) => {
+            let depth = depth.parse::<usize>().map_err(|_| ParseError::InvalidNumber(depth.to_string()))?;
+            DepthLimitedSolver::from_board(board, depth).get_next_moves_and_evaluation()?
+        },
+        None => AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation()?,
+    };
+    let next_moves_string = next_moves.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("{}\n\nEvaluation: {}\nIndifferent between these moves:\n{}", board.to_string(), evaluation, next_moves_string);
+    Ok(())
+}
+
+fn solve_random_turn(matches: &ArgMatches) -> Result<(), CliError> {
+    let position = matches.value_of("Position").expect("Position has a default value.");
+    let board = Board::from_position(position)?;
+    let players = match matches.value_of("Player") {
+        Some("O") => vec![Player::O],
+        Some(_) => vec![Player::X],
+        None => vec![Player::X, Player::O],
+    };
+
+    let mut solver = ExpectimaxSolver::from_board(board)?;
+    println!("\n\nBefore the coin toss: {}", solver.get_win_probabilities().to_string());
+    for player in players {
+        let move_probabilities_string = solver.get_move_probabilities(player)?.iter()
+            .map(|(m, probabilities)| format!("{}: {}", m.to_string(), probabilities.to_string()))
+            .collect::<Vec<String>>()
+            .join("\n");
+        println!("\nIf {} moves, best first:\n{}", player.to_string(), move_probabilities_string);
+    }
+    Ok(())
+}
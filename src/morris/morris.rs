@@ -0,0 +1,336 @@
+use std::sync::OnceLock;
+
+use crate::board::board::{Bitboard, Board, Move, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::board::shape::BoardShape;
+use crate::game::game::Game;
+
+const N_SQUARES: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MorrisMove {
+    Place(Move),
+    Slide { from: Move, to: Move },
+}
+
+impl MorrisMove {
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        match self {
+            MorrisMove::Place(m) => m.to_string(),
+            MorrisMove::Slide { from, to } => format!("{} -> {}", from.to_string(), to.to_string()),
+        }
+    }
+
+    /// Parses a square to place a piece on, like `"1 1"`, or a piece to
+    /// slide and where to, like `"0 0 -> 1 1"`.
+    pub fn from_string(s: &str) -> Result<MorrisMove, ParseError> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        match s.split("->").collect::<Vec<&str>>().as_slice() {
+            [to] => Ok(MorrisMove::Place(Move::from_string(to).map_err(|_| invalid())?)),
+            [from, to] => Ok(MorrisMove::Slide {
+                from: Move::from_string(from).map_err(|_| invalid())?,
+                to: Move::from_string(to).map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+}
+
+/// Three Men's Morris, and Achi with four pieces each: players take turns
+/// placing pieces on the 3x3 board until each has placed all of theirs, then
+/// take turns sliding one of their pieces along a line of the board to an
+/// adjacent empty square.  Three in a row wins, and a player who can't move
+/// loses.
+///
+/// Once pieces move, the number on the board no longer says whose turn it is,
+/// so the player to move is kept explicitly.  Positions can recur, so games
+/// are solved with `RetrogradeSolver`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MorrisBoard {
+    board: Board,
+    active_player: Player,
+    n_pieces: usize,
+}
+
+impl MorrisBoard {
+
+    pub fn empty(n_pieces: usize) -> Self {
+        MorrisBoard { board: Board::empty(), active_player: Player::X, n_pieces }
+    }
+
+    pub fn three_mens_morris() -> Self {
+        MorrisBoard::empty(3)
+    }
+
+    pub fn achi() -> Self {
+        MorrisBoard::empty(4)
+    }
+
+    /// Parses a 3x3 position in which each player has at most `n_pieces`
+    /// pieces.  While pieces are being placed, X is to move when both players
+    /// have placed as many; after that, X is assumed to be to move, which
+    /// `with_active_player` can change.  Squares marked `#` are blocked, and
+    /// pieces can neither be placed on them nor slide onto them.
+    pub fn from_position(position: &str, n_pieces: usize) -> Result<Self, ParseError> {
+        let board = Board::from_position(position)?;
+        if !board.get_shape().is_standard() {
+            return Err(ParseError::InvalidPositionLength { position: position.to_string(), length: board.get_shape().n_squares() });
+        }
+        let n_x = board.get_x_bitboard().n_set();
+        let n_o = board.get_o_bitboard().n_set();
+        if n_x > n_pieces {
+            return Err(ParseError::TooManyPieces(Player::X));
+        }
+        if n_o > n_pieces {
+            return Err(ParseError::TooManyPieces(Player::O));
+        }
+        let active_player = match n_x > n_o {
+            true => Player::O,
+            false => Player::X,
+        };
+        Ok(MorrisBoard { board, active_player, n_pieces })
+    }
+
+    pub fn with_active_player(&self, active_player: Player) -> Self {
+        MorrisBoard { active_player, ..*self }
+    }
+
+    pub fn get_board(&self) -> Board {
+        self.board
+    }
+
+    pub fn get_n_pieces(&self) -> usize {
+        self.n_pieces
+    }
+
+    fn get_pieces(&self, player: Player) -> Bitboard {
+        match player {
+            Player::X => self.board.get_x_bitboard(),
+            Player::O => self.board.get_o_bitboard(),
+        }
+    }
+
+    /// Whether `player` still has pieces to place.
+    pub fn is_placing(&self, player: Player) -> bool {
+        self.get_pieces(player).n_set() < self.n_pieces
+    }
+
+    /// The squares a piece on `square` can slide to along the lines of the
+    /// board, empty or not: its neighbours in any row, column or diagonal.
+    pub fn get_adjacent_squares(square: usize) -> Bitboard {
+        static ADJACENT_SQUARES: OnceLock<[Bitboard; N_SQUARES]> = OnceLock::new();
+        ADJACENT_SQUARES.get_or_init(|| {
+            let mut adjacent_squares = [Bitboard::empty(); N_SQUARES];
+            for mask in BoardShape::standard().get_win_masks() {
+                let squares = mask.get_squares();
+                for pair in squares.windows(2) {
+                    adjacent_squares[pair[0]].set_square(pair[1]);
+                    adjacent_squares[pair[1]].set_square(pair[0]);
+                }
+            }
+            adjacent_squares
+        })[square]
+    }
+
+    pub fn get_outcome(&self) -> Outcome {
+        let shape = self.board.get_shape();
+        match (shape.is_victory(self.board.get_x_bitboard()), shape.is_victory(self.board.get_o_bitboard())) {
+            (true, true) => Outcome::Ambiguous,
+            (true, false) => Outcome::Victory(Player::X),
+            (false, true) => Outcome::Victory(Player::O),
+            (false, false) if self.get_moves().is_empty() => Outcome::Victory(self.active_player.other()),
+            (false, false) => Outcome::InProgress,
+        }
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        match self.get_outcome() {
+            Outcome::InProgress => Some(self.active_player),
+            _ => None,
+        }
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<MorrisMove> {
+        match self.get_active_player() {
+            Some(_) => self.get_moves(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The squares with neither a piece nor a block on them.
+    fn get_empty_squares(&self) -> Bitboard {
+        let occupied = self.board.get_x_bitboard().union(&self.board.get_o_bitboard()).union(&self.board.get_blocked_bitboard());
+        self.board.get_shape().get_full_bitboard().difference(&occupied)
+    }
+
+    /// The moves of the player to move, whether or not the game is over.
+    fn get_moves(&self) -> Vec<MorrisMove> {
+        let shape = self.board.get_shape();
+        let empty = self.get_empty_squares();
+        if self.is_placing(self.active_player) {
+            return empty.get_squares().into_iter()
+                .map(|square| MorrisMove::Place(shape.get_move(square)))
+                .collect();
+        }
+        let mut moves = Vec::new();
+        for from in self.get_pieces(self.active_player).get_squares() {
+            for to in MorrisBoard::get_adjacent_squares(from).intersection(&empty).get_squares() {
+                moves.push(MorrisMove::Slide { from: shape.get_move(from), to: shape.get_move(to) });
+            }
+        }
+        moves
+    }
+
+    pub fn make_move(&mut self, player: Player, m: MorrisMove) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        let shape = self.board.get_shape();
+        let mut pieces = self.get_pieces(player);
+        match m {
+            MorrisMove::Place(_) if !self.is_placing(player) => return Err(MoveError::WrongPhase),
+            MorrisMove::Place(m) => {
+                self.board.place_mark(player, m)?;
+                self.active_player = player.other();
+                return Ok(());
+            },
+            MorrisMove::Slide { .. } if self.is_placing(player) => return Err(MoveError::WrongPhase),
+            MorrisMove::Slide { from, to } => {
+                if !shape.contains(from) || !pieces.is_square_set(shape.get_square(from.get_row(), from.get_column())) {
+                    return Err(MoveError::NotOwnPiece(from));
+                }
+                if !shape.contains(to) {
+                    return Err(MoveError::OutOfBounds(to));
+                }
+                let from_square = shape.get_square(from.get_row(), from.get_column());
+                let to_square = shape.get_square(to.get_row(), to.get_column());
+                if self.board.get_blocked_bitboard().is_square_set(to_square) {
+                    return Err(MoveError::SquareBlocked(to));
+                }
+                if !self.get_empty_squares().is_square_set(to_square) {
+                    return Err(MoveError::SquareOccupied(to));
+                }
+                if !MorrisBoard::get_adjacent_squares(from_square).is_square_set(to_square) {
+                    return Err(MoveError::NotAdjacent { from, to });
+                }
+                pieces.clear_square(from_square);
+                pieces.set_square(to_square);
+            },
+        }
+        self.board = match player {
+            Player::X => Board::new(pieces, self.board.get_o_bitboard()),
+            Player::O => Board::new(self.board.get_x_bitboard(), pieces),
+        }.with_blocked_squares(self.board.get_blocked_bitboard()).expect("Pieces only slide onto empty squares.");
+        self.active_player = player.other();
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, player: Player, m: MorrisMove) -> Result<Self, MoveError> {
+        let mut new_board = *self;
+        new_board.make_move(player, m)?;
+        Ok(new_board)
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.board.to_string()
+    }
+
+}
+
+impl Game for MorrisBoard {
+    type Move = MorrisMove;
+
+    fn get_outcome(&self) -> Outcome {
+        MorrisBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        MorrisBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<MorrisMove> {
+        MorrisBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: MorrisMove) -> Self {
+        let player = MorrisBoard::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+}
+
+
+#[cfg(test)]
+mod test_morris {
+    use super::*;
+    use crate::solver::retrograde::RetrogradeSolver;
+    use crate::solver::evaluation::Evaluation;
+
+    #[test]
+    fn test_pieces_are_placed_then_slid_along_lines() {
+        assert_eq!(MorrisBoard::get_adjacent_squares(0).get_squares(), vec![1, 3, 4]);
+        assert_eq!(MorrisBoard::get_adjacent_squares(1).get_squares(), vec![0, 2, 4]);
+        assert_eq!(MorrisBoard::get_adjacent_squares(4).n_set(), 8);
+
+        let board = MorrisBoard::from_position("XO_ _X_ O__", 3).unwrap();
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert_eq!(board.with_move_made(Player::X, MorrisMove::Slide { from: Move::new(0, 0), to: Move::new(1, 0) }), Err(MoveError::WrongPhase));
+        let board = board.with_move_made(Player::X, MorrisMove::Place(Move::new(1, 2))).unwrap();
+        let board = board.with_move_made(Player::O, MorrisMove::Place(Move::new(2, 2))).unwrap();
+        assert_eq!(board.with_move_made(Player::X, MorrisMove::Place(Move::new(1, 0))), Err(MoveError::WrongPhase));
+        assert_eq!(board.get_legal_moves().len(), 5);
+        assert_eq!(
+            board.with_move_made(Player::X, MorrisMove::Slide { from: Move::new(1, 2), to: Move::new(2, 1) }),
+            Err(MoveError::NotAdjacent { from: Move::new(1, 2), to: Move::new(2, 1) })
+        );
+        assert_eq!(
+            board.with_move_made(Player::X, MorrisMove::Slide { from: Move::new(0, 1), to: Move::new(0, 2) }),
+            Err(MoveError::NotOwnPiece(Move::new(0, 1)))
+        );
+        let board = board.with_move_made(Player::X, MorrisMove::Slide { from: Move::new(0, 0), to: Move::new(1, 0) }).unwrap();
+        assert_eq!(board.get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(MorrisMove::from_string("0 0 -> 1 0"), Ok(MorrisMove::Slide { from: Move::new(0, 0), to: Move::new(1, 0) }));
+    }
+
+    #[test]
+    fn test_a_player_who_cannot_move_loses() {
+        let board = MorrisBoard::from_position("OOX XXO _XO", 4).unwrap().with_active_player(Player::O);
+        assert_eq!(board.get_outcome(), Outcome::Victory(Player::X));
+    }
+
+    #[test]
+    fn test_pieces_stay_off_blocked_squares() {
+        let board = MorrisBoard::from_position("XO# OX_ X_O", 3).unwrap();
+        assert!(board.get_legal_moves().iter().all(|m| !matches!(m, MorrisMove::Slide { to, .. } if *to == Move::new(0, 2))));
+        assert_eq!(
+            board.with_move_made(Player::X, MorrisMove::Slide { from: Move::new(1, 1), to: Move::new(0, 2) }),
+            Err(MoveError::SquareBlocked(Move::new(0, 2)))
+        );
+        let board = board.with_move_made(Player::X, MorrisMove::Slide { from: Move::new(1, 1), to: Move::new(1, 2) }).unwrap();
+        assert_eq!(board.to_string(), "XO#\nO_X\nX_O");
+
+        let solver = RetrogradeSolver::from_board(MorrisBoard::from_position("#________", 3).unwrap());
+        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![MorrisMove::Place(Move::new(1, 1))], Evaluation::win(Player::X, 13))));
+    }
+
+    #[test]
+    fn test_morris_games_are_solved_despite_cycles() {
+        let solver = RetrogradeSolver::from_board(MorrisBoard::three_mens_morris());
+        assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![MorrisMove::Place(Move::new(1, 1))], Evaluation::win(Player::X, 9))));
+        assert_eq!(RetrogradeSolver::from_board(MorrisBoard::achi()).get_evaluation(), Evaluation::win(Player::X, 11));
+
+        // O can only keep X from lining up by shuffling back and forth.
+        let board = MorrisBoard::from_position("XOX/OXO/___", 3).unwrap().with_active_player(Player::O);
+        let (evaluation, line) = RetrogradeSolver::from_board(board).get_evaluation_and_line();
+        assert_eq!(evaluation, Evaluation::draw());
+        assert_eq!(line.len(), 4);
+        let end = line.iter().zip([Player::O, Player::X].iter().cycle()).fold(board, |board, (m, player)| board.with_move_made(*player, *m).unwrap());
+        assert_eq!(end, board);
+    }
+
+}
@@ -0,0 +1,235 @@
+use crate::board::board::{Board, Move, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::game::game::Game;
+use crate::solver::evaluation::Evaluation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotaktoMove {
+    board: usize,
+    square: Move,
+}
+
+impl NotaktoMove {
+
+    pub fn new(board: usize, square: Move) -> Self {
+        NotaktoMove { board, square }
+    }
+
+    pub fn get_board(&self) -> usize {
+        self.board
+    }
+
+    pub fn get_square(&self) -> Move {
+        self.square
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("Board {} {}", self.board, self.square.to_string())
+    }
+
+}
+
+/// Notakto: both players place X's on any of several boards, a board is
+/// finished once it has three in a row, and whoever finishes the last board
+/// loses.
+///
+/// The players only differ in whose turn it is, so `Player::X` here is simply
+/// whoever moves first from the starting boards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Notakto {
+    boards: Vec<Board>,
+    active_player: Player,
+}
+
+impl Notakto {
+
+    /// Starts a game from `boards`, which may only contain X's.  Blocked
+    /// squares could leave a board with no moves and no line, which the
+    /// rules don't cover, so they aren't allowed either.
+    pub fn new(boards: Vec<Board>) -> Result<Self, ParseError> {
+        if boards.iter().any(|board| !board.get_o_bitboard().is_empty()) {
+            return Err(ParseError::InvalidCharacter('O'));
+        }
+        if boards.iter().any(|board| !board.get_blocked_bitboard().is_empty()) {
+            return Err(ParseError::InvalidCharacter('#'));
+        }
+        Ok(Notakto { boards, active_player: Player::X })
+    }
+
+    pub fn from_positions(positions: &[&str]) -> Result<Self, ParseError> {
+        let boards = positions.iter()
+            .map(|position| Board::from_position(position))
+            .collect::<Result<Vec<Board>, ParseError>>()?;
+        Notakto::new(boards)
+    }
+
+    pub fn get_boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    pub fn is_finished(&self, board: usize) -> bool {
+        let board = &self.boards[board];
+        board.get_shape().is_victory(board.get_x_bitboard())
+    }
+
+    pub fn n_live_boards(&self) -> usize {
+        (0..self.boards.len()).filter(|board| !self.is_finished(*board)).count()
+    }
+
+    pub fn get_outcome(&self) -> Outcome {
+        match self.n_live_boards() {
+            0 => Outcome::Victory(self.active_player),
+            _ => Outcome::InProgress,
+        }
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        match self.n_live_boards() {
+            0 => None,
+            _ => Some(self.active_player),
+        }
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<NotaktoMove> {
+        let mut moves = Vec::new();
+        for (i, board) in self.boards.iter().enumerate() {
+            if self.is_finished(i) {
+                continue;
+            }
+            for square in board.get_legal_moves() {
+                moves.push(NotaktoMove::new(i, square));
+            }
+        }
+        moves
+    }
+
+    pub fn make_move(&mut self, m: NotaktoMove) -> Result<(), MoveError> {
+        if self.get_active_player().is_none() {
+            return Err(MoveError::GameOver);
+        }
+        if m.board >= self.boards.len() {
+            return Err(MoveError::NoSuchBoard(m.board));
+        }
+        if self.is_finished(m.board) {
+            return Err(MoveError::BoardFinished(m.board));
+        }
+        self.boards[m.board].place_mark(Player::X, m.square)?;
+        self.active_player = self.active_player.other();
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, m: NotaktoMove) -> Result<Self, MoveError> {
+        let mut new_game = self.clone();
+        new_game.make_move(m)?;
+        Ok(new_game)
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.boards.iter().enumerate()
+            .map(|(i, board)| match self.is_finished(i) {
+                true => format!("Board {} (finished):\n{}", i, board.to_string()),
+                false => format!("Board {}:\n{}", i, board.to_string()),
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+
+    /// Describes an evaluation in terms of the first and second player, since
+    /// both of them play X.
+    pub fn evaluation_to_string(evaluation: &Evaluation) -> String {
+        evaluation.to_string_by_turn_order()
+    }
+
+}
+
+impl Game for Notakto {
+    type Move = NotaktoMove;
+
+    fn get_outcome(&self) -> Outcome {
+        Notakto::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        Notakto::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<NotaktoMove> {
+        Notakto::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: NotaktoMove) -> Self {
+        self.with_move_made(m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    /// Finished boards can't affect the rest of the game and the order of the
+    /// boards doesn't matter, so positions are keyed by the canonical forms of
+    /// their live boards, sorted, with the player to move normalized away.
+    fn get_transposition_key(&self) -> Self {
+        let mut boards = (0..self.boards.len())
+            .filter(|board| !self.is_finished(*board))
+            .map(|board| self.boards[board].get_canonical_form().0)
+            .collect::<Vec<Board>>();
+        boards.sort();
+        Notakto { boards, active_player: Player::X }
+    }
+}
+
+
+#[cfg(test)]
+mod test_notakto {
+    use super::*;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+
+    fn is_won_by_player_to_move(game: &Notakto) -> bool {
+        match game.get_active_player() {
+            None => true,
+            Some(_) => game.get_legal_moves().iter().any(|m| !is_won_by_player_to_move(&game.with_move_made(*m).unwrap())),
+        }
+    }
+
+    #[test]
+    fn test_notakto_finishes_boards_and_ends() {
+        let mut game = Notakto::from_positions(&["XX_ ___ ___", "X_X _X_ X_X"]).unwrap();
+        assert_eq!(game.n_live_boards(), 1);
+        assert_eq!(game.get_legal_moves().len(), 7);
+        assert_eq!(game.make_move(NotaktoMove::new(1, Move::new(0, 1))), Err(MoveError::BoardFinished(1)));
+        assert_eq!(game.make_move(NotaktoMove::new(2, Move::new(0, 1))), Err(MoveError::NoSuchBoard(2)));
+        assert_eq!(game.make_move(NotaktoMove::new(0, Move::new(0, 0))), Err(MoveError::SquareOccupied(Move::new(0, 0))));
+        game.make_move(NotaktoMove::new(0, Move::new(0, 2))).unwrap();
+        assert_eq!(game.get_outcome(), Outcome::Victory(Player::O));
+        assert_eq!(game.get_active_player(), None);
+        assert_eq!(Notakto::from_positions(&["XO_ ___ ___"]), Err(ParseError::InvalidCharacter('O')));
+        assert_eq!(Notakto::from_positions(&["X#_ ___ ___"]), Err(ParseError::InvalidCharacter('#')));
+    }
+
+    #[test]
+    fn test_first_player_wins_single_board_notakto_in_the_centre() {
+        let game = Notakto::from_positions(&["_________"]).unwrap();
+        let (next_moves, evaluation) = AlphaBetaSolver::from_board(game).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(next_moves, vec![NotaktoMove::new(0, Move::new(1, 1))]);
+        assert_eq!(evaluation.get_winner(), Some(Player::X));
+    }
+
+    #[test]
+    fn test_notakto_solver_agrees_with_brute_force() {
+        let positions = vec![
+            vec!["___ ___ ___"],
+            vec!["X__ _X_ ___", "_X_ X__ __X"],
+            vec!["X__ ___ __X", "_X_ X__ ___"],
+            vec!["XX_ ___ _X_", "_X_ _X_ ___", "X_X ___ ___"],
+            vec!["X_X ___ X_X", "_X_ X_X _X_"],
+        ];
+        for position in positions {
+            let game = Notakto::from_positions(&position).unwrap();
+            let evaluation = AlphaBetaSolver::from_board(game.clone()).get_evaluation();
+            let winner = match is_won_by_player_to_move(&game) {
+                true => Player::X,
+                false => Player::O,
+            };
+            assert_eq!(evaluation.get_winner(), Some(winner), "{}", game.to_string());
+        }
+    }
+
+}
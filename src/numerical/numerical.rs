@@ -0,0 +1,308 @@
+use crate::board::board::{Bitboard, Move, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::board::shape::BoardShape;
+use crate::board::symmetry::Symmetry;
+use crate::game::game::Game;
+
+const N_SQUARES: usize = 9;
+const TARGET_SUM: u8 = 15;
+
+/// A move in numerical tic-tac-toe: a square, and the number to write there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NumericalMove {
+    square: Move,
+    number: u8,
+}
+
+impl NumericalMove {
+
+    pub fn new(square: Move, number: u8) -> Self {
+        NumericalMove { square, number }
+    }
+
+    pub fn get_square(&self) -> Move {
+        self.square
+    }
+
+    pub fn get_number(&self) -> u8 {
+        self.number
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("{} at {}", self.number, self.square.to_string())
+    }
+
+    /// Parses the number followed by the square, like `"5 1 1"`, `"5,1,1"` or
+    /// `"5 11"`.
+    pub fn from_string(s: &str) -> Result<NumericalMove, ParseError> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        let s = s.trim();
+        let number = match s.chars().next().and_then(|c| c.to_digit(10)) {
+            Some(number) if number > 0 => number as u8,
+            _ => return Err(invalid()),
+        };
+        let square = s[1..].trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let square = Move::from_string(square).map_err(|_| invalid())?;
+        Ok(NumericalMove::new(square, number))
+    }
+
+}
+
+/// Numerical tic-tac-toe: the first player writes the odd numbers 1-9 and the
+/// second the even ones, each number at most once, and whoever completes a
+/// line of three numbers adding up to 15 wins.
+///
+/// Squares hold their number, or 0 while empty.  The first player is
+/// `Player::X`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NumericalBoard {
+    squares: [u8; N_SQUARES],
+}
+
+impl NumericalBoard {
+
+    pub fn empty() -> Self {
+        NumericalBoard { squares: [0; N_SQUARES] }
+    }
+
+    /// Parses a 3x3 position of digits 1-9 and `_` for empty squares, with
+    /// rows optionally separated by `/` or whitespace, like `"5_2/___/__8"`.
+    /// The odd player moves first, so there must be as many odd numbers as
+    /// even ones or one more.
+    pub fn from_position(position: &str) -> Result<Self, ParseError> {
+        let squares = position.chars().filter(|c| *c != '/' && !c.is_whitespace()).collect::<Vec<char>>();
+        if squares.len() != N_SQUARES {
+            return Err(ParseError::InvalidPositionLength { position: position.to_string(), length: squares.len() });
+        }
+        let mut board = NumericalBoard::empty();
+        for (square, c) in squares.iter().enumerate() {
+            let number = match c {
+                '_' => continue,
+                '1'..='9' => c.to_digit(10).expect("Digits 1-9 are numbers.") as u8,
+                _ => return Err(ParseError::InvalidCharacter(*c)),
+            };
+            if board.is_used(number) {
+                return Err(ParseError::RepeatedNumber(number));
+            }
+            board.squares[square] = number;
+        }
+        let n_odd = board.squares.iter().filter(|number| *number % 2 == 1).count();
+        let n_even = board.n_filled() - n_odd;
+        if n_odd != n_even && n_odd != n_even + 1 {
+            return Err(ParseError::NumberCountMismatch { n_odd, n_even });
+        }
+        Ok(board)
+    }
+
+    /// The number on `m`, or `None` if it's empty or off the board.
+    pub fn get_number(&self, m: Move) -> Option<u8> {
+        let shape = BoardShape::standard();
+        if !shape.contains(m) {
+            return None;
+        }
+        match self.squares[shape.get_square(m.get_row(), m.get_column())] {
+            0 => None,
+            number => Some(number),
+        }
+    }
+
+    pub fn is_used(&self, number: u8) -> bool {
+        self.squares.contains(&number)
+    }
+
+    fn n_filled(&self) -> usize {
+        self.squares.iter().filter(|number| **number != 0).count()
+    }
+
+    /// The numbers `player` hasn't written yet: odd ones for X, even ones for
+    /// O.
+    pub fn get_available_numbers(&self, player: Player) -> Vec<u8> {
+        let first = match player {
+            Player::X => 1,
+            Player::O => 2,
+        };
+        (first..=9).step_by(2).filter(|number| !self.is_used(*number)).collect()
+    }
+
+    fn has_line(&self) -> bool {
+        let filled = (0..N_SQUARES).filter(|square| self.squares[*square] != 0).fold(Bitboard::empty(), |mut filled, square| {
+            filled.set_square(square);
+            filled
+        });
+        BoardShape::standard().get_win_masks().iter()
+            .filter(|mask| filled.contains(**mask))
+            .any(|mask| mask.get_squares().iter().map(|square| self.squares[*square]).sum::<u8>() == TARGET_SUM)
+    }
+
+    /// A line adding up to 15 was completed by the player who moved last,
+    /// whichever of them wrote its numbers.
+    pub fn get_outcome(&self) -> Outcome {
+        let last_player = match self.n_filled() % 2 {
+            0 => Player::O,
+            _ => Player::X,
+        };
+        if self.has_line() {
+            Outcome::Victory(last_player)
+        } else if self.n_filled() == N_SQUARES {
+            Outcome::Draw
+        } else {
+            Outcome::InProgress
+        }
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        if self.get_outcome() != Outcome::InProgress {
+            return None;
+        }
+        match self.n_filled() % 2 {
+            0 => Some(Player::X),
+            _ => Some(Player::O),
+        }
+    }
+
+    /// Every empty square with every number the player to move has left,
+    /// with the moves that complete a line first so that searches find them
+    /// early.
+    pub fn get_legal_moves(&self) -> Vec<NumericalMove> {
+        let player = match self.get_active_player() {
+            Some(player) => player,
+            None => return Vec::new(),
+        };
+        let numbers = self.get_available_numbers(player);
+        let shape = BoardShape::standard();
+        let mut moves = Vec::new();
+        for square in (0..N_SQUARES).filter(|square| self.squares[*square] == 0) {
+            for number in numbers.iter() {
+                moves.push(NumericalMove::new(shape.get_move(square), *number));
+            }
+        }
+        moves.sort_by_key(|m| !self.with_move_made(player, *m).expect("Move is legal by construction.").has_line());
+        moves
+    }
+
+    pub fn make_move(&mut self, player: Player, m: NumericalMove) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        let shape = BoardShape::standard();
+        if !shape.contains(m.square) {
+            return Err(MoveError::OutOfBounds(m.square));
+        }
+        if !self.get_available_numbers(player).contains(&m.number) {
+            return Err(MoveError::NumberUnavailable(m.number));
+        }
+        let square = shape.get_square(m.square.get_row(), m.square.get_column());
+        if self.squares[square] != 0 {
+            return Err(MoveError::SquareOccupied(m.square));
+        }
+        self.squares[square] = m.number;
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, player: Player, m: NumericalMove) -> Result<Self, MoveError> {
+        let mut new_board = *self;
+        new_board.make_move(player, m)?;
+        Ok(new_board)
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.squares.chunks(3)
+            .map(|row| row.iter().map(|number| match number {
+                0 => '_',
+                _ => (b'0' + number) as char,
+            }).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Only symmetries in `Symmetry::all()` are meaningful here.
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        let shape = BoardShape::standard();
+        let mut squares = [0; N_SQUARES];
+        for (square, number) in self.squares.iter().enumerate() {
+            let m = shape.get_move(square).transformed(symmetry, &shape);
+            squares[shape.get_square(m.get_row(), m.get_column())] = *number;
+        }
+        NumericalBoard { squares }
+    }
+
+}
+
+impl Game for NumericalBoard {
+    type Move = NumericalMove;
+
+    fn get_outcome(&self) -> Outcome {
+        NumericalBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        NumericalBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<NumericalMove> {
+        NumericalBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: NumericalMove) -> Self {
+        let player = NumericalBoard::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    /// Rotations and reflections of the board keep every line a line, so
+    /// positions are keyed by their smallest image.
+    fn get_transposition_key(&self) -> Self {
+        Symmetry::all().iter()
+            .map(|symmetry| self.transformed(*symmetry))
+            .min()
+            .expect("There is always at least the identity symmetry.")
+    }
+}
+
+
+#[cfg(test)]
+mod test_numerical {
+    use super::*;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+    use crate::solver::evaluation::Evaluation;
+
+    #[test]
+    fn test_lines_adding_up_to_15_win_for_whoever_completes_them() {
+        let board = NumericalBoard::from_position("4_9/_5_/___").unwrap();
+        assert_eq!(board.get_active_player(), Some(Player::O));
+        assert_eq!(board.get_available_numbers(Player::O), vec![2, 6, 8]);
+        assert_eq!(board.get_legal_moves().len(), 18);
+        assert_eq!(board.with_move_made(Player::O, NumericalMove::new(Move::new(0, 1), 3)), Err(MoveError::NumberUnavailable(3)));
+        assert_eq!(board.with_move_made(Player::O, NumericalMove::new(Move::new(1, 1), 2)), Err(MoveError::SquareOccupied(Move::new(1, 1))));
+
+        let won = board.with_move_made(Player::O, NumericalMove::new(Move::new(0, 1), 2)).unwrap();
+        assert_eq!(won.get_outcome(), Outcome::Victory(Player::O));
+        assert_eq!(NumericalBoard::from_position(&won.to_string()), Ok(won));
+        assert_eq!(NumericalMove::from_string("2 0 1"), Ok(NumericalMove::new(Move::new(0, 1), 2)));
+        assert_eq!(NumericalBoard::from_position("5_5/___/___"), Err(ParseError::RepeatedNumber(5)));
+        assert_eq!(board.get_number(Move::new(0, 2)), Some(9));
+        assert_eq!(board.get_number(Move::new(3, 0)), None);
+    }
+
+    #[test]
+    fn test_positions_must_follow_the_turn_order() {
+        assert_eq!(NumericalBoard::from_position("2__/___/___"), Err(ParseError::NumberCountMismatch { n_odd: 0, n_even: 1 }));
+        assert_eq!(NumericalBoard::from_position("246/___/___"), Err(ParseError::NumberCountMismatch { n_odd: 0, n_even: 3 }));
+        assert_eq!(NumericalBoard::from_position("135/___/___"), Err(ParseError::NumberCountMismatch { n_odd: 3, n_even: 0 }));
+        assert!(NumericalBoard::from_position("12_/3__/___").is_ok());
+    }
+
+    #[test]
+    fn test_first_player_wins_numerical_tic_tac_toe() {
+        assert_eq!(AlphaBetaSolver::from_board(NumericalBoard::empty()).get_evaluation(), Evaluation::win(Player::X, 9));
+
+        let board = NumericalBoard::from_position("1_2/_5_/4__").unwrap();
+        let (next_moves, evaluation) = AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(next_moves, vec![NumericalMove::new(Move::new(2, 2), 9)]);
+        assert_eq!(evaluation, Evaluation::win(Player::X, 1));
+    }
+
+}
@@ -0,0 +1,2 @@
+#[allow(clippy::module_inception)]
+pub mod order_and_chaos;
\ No newline at end of file
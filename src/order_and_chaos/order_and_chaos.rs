@@ -0,0 +1,220 @@
+use crate::board::board::{Bitboard, Board, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::board::shape::BoardShape;
+use crate::game::game::Game;
+use crate::solver::evaluation::Evaluation;
+use crate::wild::wild::WildMove;
+
+const SIZE: usize = 6;
+const WIN_LENGTH: usize = 5;
+
+/// Heuristic weight of a line of five holding only one symbol, by how many
+/// of that symbol it holds.
+const LINE_SCORES: [i32; 6] = [0, 1, 4, 16, 64, 256];
+
+/// Order and Chaos: on a 6x6 board, each player puts either symbol on an
+/// empty square.  Order wins as soon as there are five or more in a row of
+/// either symbol, whoever placed them; Chaos wins if the board fills up
+/// without that.
+///
+/// Order moves first and is `Player::X`; Chaos is `Player::O`.  Moves are
+/// `WildMove`s, carrying the symbol alongside the square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OrderAndChaos {
+    board: Board,
+}
+
+impl OrderAndChaos {
+
+    pub fn empty() -> Self {
+        let shape = BoardShape::new(SIZE, SIZE, WIN_LENGTH).expect("Five in a row fits on a 6x6 board.");
+        OrderAndChaos { board: Board::empty_with_shape(shape) }
+    }
+
+    /// Parses a 6x6 position with rows separated by `/` or whitespace.
+    pub fn from_position(position: &str) -> Result<Self, ParseError> {
+        let board = Board::from_position_with_win_length(position, WIN_LENGTH)?;
+        let shape = board.get_shape();
+        if shape.get_width() != SIZE || shape.get_height() != SIZE {
+            return Err(ParseError::InvalidPositionLength { position: position.to_string(), length: shape.n_squares() });
+        }
+        Ok(OrderAndChaos { board })
+    }
+
+    pub fn get_board(&self) -> Board {
+        self.board
+    }
+
+    pub fn get_outcome(&self) -> Outcome {
+        let shape = self.board.get_shape();
+        if shape.is_victory(self.board.get_x_bitboard()) || shape.is_victory(self.board.get_o_bitboard()) {
+            Outcome::Victory(Player::X)
+        } else if self.board.is_full() {
+            Outcome::Victory(Player::O)
+        } else {
+            Outcome::InProgress
+        }
+    }
+
+    /// Every turn places one mark, so Order is to move whenever an even number
+    /// have been placed.
+    pub fn get_active_player(&self) -> Option<Player> {
+        if self.get_outcome() != Outcome::InProgress {
+            return None;
+        }
+        match self.board.get_x_bitboard().union(&self.board.get_o_bitboard()).n_set() % 2 {
+            0 => Some(Player::X),
+            _ => Some(Player::O),
+        }
+    }
+
+    /// Every empty square with either symbol, ordered so that searches try
+    /// first the moves that extend or block the fullest lines.
+    pub fn get_legal_moves(&self) -> Vec<WildMove> {
+        if self.get_active_player().is_none() {
+            return Vec::new();
+        }
+        let shape = self.board.get_shape();
+        let mut moves = Vec::new();
+        for square in self.board.get_legal_moves() {
+            for symbol in [Player::X, Player::O] {
+                let (own, other) = self.get_bitboards(symbol);
+                let score = self.board.get_open_win_masks()
+                    .filter(|mask| mask.is_square_set(shape.get_square(square.get_row(), square.get_column())))
+                    .map(|mask| match (own.intersection(mask).n_set(), other.intersection(mask).n_set()) {
+                        (n_own, 0) => LINE_SCORES[n_own],
+                        (0, n_other) => LINE_SCORES[n_other],
+                        _ => 0,
+                    })
+                    .sum::<i32>();
+                moves.push((std::cmp::Reverse(score), WildMove::new(square, symbol)));
+            }
+        }
+        moves.sort_by_key(|(score, _)| *score);
+        moves.into_iter().map(|(_, m)| m).collect()
+    }
+
+    fn get_bitboards(&self, symbol: Player) -> (Bitboard, Bitboard) {
+        match symbol {
+            Player::X => (self.board.get_x_bitboard(), self.board.get_o_bitboard()),
+            Player::O => (self.board.get_o_bitboard(), self.board.get_x_bitboard()),
+        }
+    }
+
+    pub fn make_move(&mut self, player: Player, m: WildMove) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        self.board.place_mark(m.get_symbol(), m.get_square())
+    }
+
+    pub fn with_move_made(&self, player: Player, m: WildMove) -> Result<Self, MoveError> {
+        let mut new_game = *self;
+        new_game.make_move(player, m)?;
+        Ok(new_game)
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.board.to_string()
+    }
+
+    /// Scores lines of five, from Order's perspective, that only hold one
+    /// symbol and so could still be completed.  With none left, Chaos is
+    /// certain to win.
+    pub fn get_heuristic(&self) -> i32 {
+        let mut score = 0;
+        let mut any_open = false;
+        for mask in self.board.get_open_win_masks() {
+            match (self.board.get_x_bitboard().intersection(mask).n_set(), self.board.get_o_bitboard().intersection(mask).n_set()) {
+                (n_x, 0) => {
+                    any_open = true;
+                    score += LINE_SCORES[n_x];
+                },
+                (0, n_o) => {
+                    any_open = true;
+                    score += LINE_SCORES[n_o];
+                },
+                _ => (),
+            }
+        }
+        match any_open {
+            true => score,
+            false => -LINE_SCORES[WIN_LENGTH] * 100,
+        }
+    }
+
+    /// Describes an evaluation in terms of Order and Chaos.
+    pub fn evaluation_to_string(evaluation: &Evaluation) -> String {
+        match evaluation {
+            Evaluation::Unknown(score) => format!("Unknown ({:+}, positive favours Order)", score),
+            _ => evaluation.to_string_with_player_names("Order", "Chaos"),
+        }
+    }
+
+}
+
+impl Game for OrderAndChaos {
+    type Move = WildMove;
+
+    fn get_outcome(&self) -> Outcome {
+        OrderAndChaos::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        OrderAndChaos::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<WildMove> {
+        OrderAndChaos::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: WildMove) -> Self {
+        let player = OrderAndChaos::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    fn get_heuristic(&self) -> i32 {
+        OrderAndChaos::get_heuristic(self)
+    }
+}
+
+
+#[cfg(test)]
+mod test_order_and_chaos {
+    use super::*;
+    use crate::board::board::Move;
+    use crate::solver::depth_limited::DepthLimitedSolver;
+
+    #[test]
+    fn test_order_wins_with_either_symbol_and_chaos_by_filling_the_board() {
+        let game = OrderAndChaos::from_position("OOOO__/X_____/X_____/X_____/X_____/______").unwrap();
+        assert_eq!(game.get_active_player(), Some(Player::X));
+        assert_eq!(game.get_legal_moves().len(), 56);
+        let won = game.with_move_made(Player::X, WildMove::new(Move::new(5, 0), Player::X)).unwrap();
+        assert_eq!(won.get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(won.with_move_made(Player::O, WildMove::new(Move::new(0, 4), Player::O)), Err(MoveError::GameOver));
+
+        let full = OrderAndChaos::from_position("XOOXXX/XXOOXX/OOOOXO/XOXXOO/OXXXOX/XOXOOO").unwrap();
+        assert_eq!(full.get_outcome(), Outcome::Victory(Player::O));
+        assert!(OrderAndChaos::from_position("XO_ ___ ___").is_err());
+    }
+
+    #[test]
+    fn test_search_completes_and_blocks_fives() {
+        let game = OrderAndChaos::from_position("OOOO__/X_____/X_____/X_____/X_____/______").unwrap();
+        let (next_moves, evaluation) = DepthLimitedSolver::from_board(game, 1).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(evaluation, Evaluation::win(Player::X, 1));
+        assert!(next_moves.contains(&WildMove::new(Move::new(0, 4), Player::O)));
+        assert!(next_moves.contains(&WildMove::new(Move::new(5, 0), Player::X)));
+
+        // Chaos can only block one end of an open four.
+        let game = OrderAndChaos::from_position("_OOOO_/X_____/X_____/______/______/X_____").unwrap();
+        assert_eq!(game.get_active_player(), Some(Player::O));
+        let (_, evaluation) = DepthLimitedSolver::from_board(game, 2).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(evaluation, Evaluation::win(Player::X, 2));
+    }
+
+}
@@ -0,0 +1,433 @@
+use crate::board::board::{Bitboard, Move, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::board::shape::BoardShape;
+use crate::board::symmetry::Symmetry;
+use crate::game::game::Game;
+
+const N_SQUARES: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuantumMove {
+    /// A spooky mark, in two squares at once.
+    Spooky(Move, Move),
+    /// Which of its two squares the mark that closed a cycle of entanglement
+    /// ends up in.
+    Collapse(Move),
+    /// An ordinary mark, only played on the last square left.
+    Classical(Move),
+}
+
+impl QuantumMove {
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        match self {
+            QuantumMove::Spooky(first, second) => format!("{} & {}", first.to_string(), second.to_string()),
+            QuantumMove::Collapse(m) => format!("collapse to {}", m.to_string()),
+            QuantumMove::Classical(m) => m.to_string(),
+        }
+    }
+
+    /// Parses a spooky mark like `"0 0 & 1 1"`, a collapse like
+    /// `"collapse to 1 1"`, or a single square for a classical mark.
+    pub fn from_string(s: &str) -> Result<QuantumMove, ParseError> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        let s = s.trim();
+        if let Some(m) = s.strip_prefix("collapse") {
+            let m = m.trim_start();
+            let m = m.strip_prefix("to").unwrap_or(m);
+            return Ok(QuantumMove::Collapse(Move::from_string(m.trim()).map_err(|_| invalid())?));
+        }
+        match s.split('&').collect::<Vec<&str>>().as_slice() {
+            [m] => Ok(QuantumMove::Classical(Move::from_string(m.trim()).map_err(|_| invalid())?)),
+            [first, second] => Ok(QuantumMove::Spooky(
+                Move::from_string(first.trim()).map_err(|_| invalid())?,
+                Move::from_string(second.trim()).map_err(|_| invalid())?,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+}
+
+/// Goff's quantum tic-tac-toe.  Each move puts a spooky mark in two squares,
+/// entangling them.  When a mark closes a cycle of entanglement, the other
+/// player chooses which of its two squares it collapses into, which decides
+/// every mark entangled with it, and then makes their own move.  Squares
+/// with a collapsed, classical mark can't be played on, and once a single
+/// square is left it takes an ordinary mark.
+///
+/// Marks are numbered by the move that placed them, from 0, X's even and
+/// O's odd.  Each spooky mark is kept as its two squares, which makes the
+/// marks the edges of the entanglement graph between squares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct QuantumBoard {
+    spooky_marks: [Option<(usize, usize)>; N_SQUARES],
+    classical_marks: [Option<usize>; N_SQUARES],
+    n_marks: usize,
+    cycle: Option<usize>,
+}
+
+impl QuantumBoard {
+
+    pub fn empty() -> Self {
+        QuantumBoard { spooky_marks: [None; N_SQUARES], classical_marks: [None; N_SQUARES], n_marks: 0, cycle: None }
+    }
+
+    /// Replays `moves` from the empty board, X first, since the order of
+    /// the marks matters when lines are completed at the same time.
+    pub fn from_moves(moves: &[QuantumMove]) -> Result<Self, MoveError> {
+        let mut board = QuantumBoard::empty();
+        for m in moves {
+            let player = board.get_active_player().ok_or(MoveError::GameOver)?;
+            board.make_move(player, *m)?;
+        }
+        Ok(board)
+    }
+
+    fn get_mark_player(mark: usize) -> Player {
+        match mark % 2 {
+            0 => Player::X,
+            _ => Player::O,
+        }
+    }
+
+    /// The number of the move whose mark has collapsed into `m`, if any.
+    pub fn get_classical_mark(&self, m: Move) -> Option<usize> {
+        self.classical_marks[BoardShape::standard().get_square(m.get_row(), m.get_column())]
+    }
+
+    /// The mark that closed a cycle of entanglement and is waiting to be
+    /// collapsed, if any.
+    pub fn get_cycle(&self) -> Option<usize> {
+        self.cycle
+    }
+
+    /// Whether spooky marks link `from` to `to`, through any number of
+    /// squares.
+    fn are_entangled(&self, from: usize, to: usize) -> bool {
+        let mut reached = vec![from];
+        let mut queue = vec![from];
+        while let Some(square) = queue.pop() {
+            for (first, second) in self.spooky_marks.iter().flatten() {
+                let next = match (*first == square, *second == square) {
+                    (true, _) => *second,
+                    (_, true) => *first,
+                    _ => continue,
+                };
+                if !reached.contains(&next) {
+                    reached.push(next);
+                    queue.push(next);
+                }
+            }
+        }
+        reached.contains(&to)
+    }
+
+    /// Puts `mark` in `square` and pushes every other spooky mark there into
+    /// its other square, and so on, until the whole entangled group has
+    /// collapsed.
+    fn collapse(&mut self, mark: usize, square: usize) {
+        let mut queue = vec![(mark, square)];
+        while let Some((mark, square)) = queue.pop() {
+            self.spooky_marks[mark] = None;
+            self.classical_marks[square] = Some(mark);
+            for (other_mark, squares) in self.spooky_marks.iter_mut().enumerate() {
+                if let Some((first, second)) = *squares {
+                    if first == square || second == square {
+                        *squares = None;
+                        queue.push((other_mark, if first == square { second } else { first }));
+                    }
+                }
+            }
+        }
+    }
+
+    /// For `player`'s classical lines, the latest mark of the line completed
+    /// first, which breaks ties between lines completed by one collapse.
+    fn get_first_line(&self, player: Player) -> Option<usize> {
+        let shape = BoardShape::standard();
+        let marks = (0..N_SQUARES)
+            .filter(|square| self.classical_marks[*square].is_some_and(|mark| QuantumBoard::get_mark_player(mark) == player))
+            .fold(Bitboard::empty(), |mut marks, square| {
+                marks.set_square(square);
+                marks
+            });
+        shape.get_win_masks().iter()
+            .filter(|mask| marks.contains(**mask))
+            .filter_map(|mask| mask.get_squares().into_iter().filter_map(|square| self.classical_marks[square]).max())
+            .min()
+    }
+
+    /// The points `player` scores: a collapse can complete lines for both
+    /// players at once, and then the line whose latest mark is earlier is
+    /// worth a point and the other half a point.
+    pub fn get_points(&self, player: Player) -> f64 {
+        match (self.get_first_line(player), self.get_first_line(player.other())) {
+            (Some(line), Some(other_line)) if other_line < line => 0.5,
+            (Some(_), _) => 1.0,
+            (None, _) => 0.0,
+        }
+    }
+
+    /// Whoever scores the full point wins.
+    pub fn get_outcome(&self) -> Outcome {
+        match (self.get_first_line(Player::X), self.get_first_line(Player::O)) {
+            (Some(x_line), Some(o_line)) if o_line < x_line => Outcome::Victory(Player::O),
+            (Some(_), _) => Outcome::Victory(Player::X),
+            (None, Some(_)) => Outcome::Victory(Player::O),
+            (None, None) if self.classical_marks.iter().all(|mark| mark.is_some()) => Outcome::Draw,
+            (None, None) => Outcome::InProgress,
+        }
+    }
+
+    /// The player who didn't close a cycle collapses it, and then moves, so
+    /// the player to move is always the owner of the next mark.
+    pub fn get_active_player(&self) -> Option<Player> {
+        match self.get_outcome() {
+            Outcome::InProgress => Some(QuantumBoard::get_mark_player(self.n_marks)),
+            _ => None,
+        }
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<QuantumMove> {
+        if self.get_active_player().is_none() {
+            return Vec::new();
+        }
+        let shape = BoardShape::standard();
+        if let Some(mark) = self.cycle {
+            let (first, second) = self.spooky_marks[mark].expect("The mark that closed a cycle is spooky.");
+            return vec![QuantumMove::Collapse(shape.get_move(first)), QuantumMove::Collapse(shape.get_move(second))];
+        }
+        let open = (0..N_SQUARES).filter(|square| self.classical_marks[*square].is_none()).collect::<Vec<usize>>();
+        if let [square] = open.as_slice() {
+            return vec![QuantumMove::Classical(shape.get_move(*square))];
+        }
+        let mut moves = Vec::new();
+        for (i, first) in open.iter().enumerate() {
+            for second in open[i + 1..].iter() {
+                moves.push(QuantumMove::Spooky(shape.get_move(*first), shape.get_move(*second)));
+            }
+        }
+        moves
+    }
+
+    pub fn make_move(&mut self, player: Player, m: QuantumMove) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        let shape = BoardShape::standard();
+        let get_open_square = |m: Move| {
+            if !shape.contains(m) {
+                return Err(MoveError::OutOfBounds(m));
+            }
+            let square = shape.get_square(m.get_row(), m.get_column());
+            match self.classical_marks[square] {
+                Some(_) => Err(MoveError::SquareOccupied(m)),
+                None => Ok(square),
+            }
+        };
+        match (m, self.cycle) {
+            (QuantumMove::Collapse(m), Some(mark)) => {
+                let (first, second) = self.spooky_marks[mark].expect("The mark that closed a cycle is spooky.");
+                let square = get_open_square(m)?;
+                if square != first && square != second {
+                    return Err(MoveError::NotInCycle(m));
+                }
+                self.collapse(mark, square);
+                self.cycle = None;
+            },
+            (QuantumMove::Collapse(_), None) => return Err(MoveError::NoCycle),
+            (_, Some(_)) => return Err(MoveError::CollapsePending),
+            (QuantumMove::Spooky(first, second), None) => {
+                let (first_square, second_square) = (get_open_square(first)?, get_open_square(second)?);
+                if first_square == second_square {
+                    return Err(MoveError::SameSquare(first));
+                }
+                if self.are_entangled(first_square, second_square) {
+                    self.cycle = Some(self.n_marks);
+                }
+                self.spooky_marks[self.n_marks] = Some((first_square, second_square));
+                self.n_marks += 1;
+            },
+            (QuantumMove::Classical(m), None) => {
+                let square = get_open_square(m)?;
+                if self.classical_marks.iter().filter(|mark| mark.is_none()).count() > 1 {
+                    return Err(MoveError::NotLastSquare(m));
+                }
+                self.classical_marks[square] = Some(self.n_marks);
+                self.n_marks += 1;
+            },
+        }
+        Ok(())
+    }
+
+    /// Only symmetries in `Symmetry::all()` are meaningful here.
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        let shape = BoardShape::standard();
+        let transform = |square: usize| {
+            let m = shape.get_move(square).transformed(symmetry, &shape);
+            shape.get_square(m.get_row(), m.get_column())
+        };
+        let mut board = QuantumBoard { classical_marks: [None; N_SQUARES], ..*self };
+        for (square, mark) in self.classical_marks.iter().enumerate() {
+            board.classical_marks[transform(square)] = *mark;
+        }
+        for squares in board.spooky_marks.iter_mut() {
+            *squares = squares.map(|(first, second)| {
+                let (first, second) = (transform(first), transform(second));
+                (first.min(second), first.max(second))
+            });
+        }
+        board
+    }
+
+    pub fn with_move_made(&self, player: Player, m: QuantumMove) -> Result<Self, MoveError> {
+        let mut new_board = *self;
+        new_board.make_move(player, m)?;
+        Ok(new_board)
+    }
+
+    /// Each square's classical mark, like `X1`, or its spooky marks, like
+    /// `x1o4`, numbered from 1 as the moves are usually counted.
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        let describe_square = |square: usize| {
+            let describe_mark = |mark: usize, classical: bool| match (QuantumBoard::get_mark_player(mark), classical) {
+                (Player::X, true) => format!("X{}", mark + 1),
+                (Player::O, true) => format!("O{}", mark + 1),
+                (Player::X, false) => format!("x{}", mark + 1),
+                (Player::O, false) => format!("o{}", mark + 1),
+            };
+            if let Some(mark) = self.classical_marks[square] {
+                return describe_mark(mark, true);
+            }
+            let spooky = self.spooky_marks.iter().enumerate()
+                .filter(|(_, squares)| squares.is_some_and(|(first, second)| first == square || second == square))
+                .map(|(mark, _)| describe_mark(mark, false))
+                .collect::<String>();
+            match spooky.is_empty() {
+                true => "_".to_string(),
+                false => spooky,
+            }
+        };
+        (0..N_SQUARES).collect::<Vec<usize>>().chunks(3)
+            .map(|row| row.iter().map(|square| describe_square(*square)).collect::<Vec<String>>().join(" "))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+}
+
+impl Game for QuantumBoard {
+    type Move = QuantumMove;
+
+    fn get_outcome(&self) -> Outcome {
+        QuantumBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        QuantumBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<QuantumMove> {
+        QuantumBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: QuantumMove) -> Self {
+        let player = QuantumBoard::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    /// Rotations and reflections of the board keep every line a line, so
+    /// positions are keyed by their smallest image.
+    fn get_transposition_key(&self) -> Self {
+        Symmetry::all().iter()
+            .map(|symmetry| self.transformed(*symmetry))
+            .min()
+            .expect("There is always at least the identity symmetry.")
+    }
+}
+
+
+#[cfg(test)]
+mod test_quantum {
+    use super::*;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+    use crate::solver::evaluation::Evaluation;
+
+    fn spooky(first: usize, second: usize) -> QuantumMove {
+        let shape = BoardShape::standard();
+        QuantumMove::Spooky(shape.get_move(first), shape.get_move(second))
+    }
+
+    fn collapse(square: usize) -> QuantumMove {
+        QuantumMove::Collapse(BoardShape::standard().get_move(square))
+    }
+
+    #[test]
+    fn test_cycles_of_entanglement_collapse_into_classical_marks() {
+        let board = QuantumBoard::from_moves(&[spooky(0, 4), spooky(0, 4)]).unwrap();
+        assert_eq!(board.get_cycle(), Some(1));
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert_eq!(board.get_legal_moves(), vec![collapse(0), collapse(4)]);
+        assert_eq!(board.with_move_made(Player::X, spooky(1, 2)), Err(MoveError::CollapsePending));
+        assert_eq!(board.with_move_made(Player::X, collapse(1)), Err(MoveError::NotInCycle(Move::new(0, 1))));
+        let board = board.with_move_made(Player::X, collapse(0)).unwrap();
+        assert_eq!(board.get_classical_mark(Move::new(0, 0)), Some(1));
+        assert_eq!(board.get_classical_mark(Move::new(1, 1)), Some(0));
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert_eq!(board.with_move_made(Player::X, collapse(0)), Err(MoveError::NoCycle));
+        assert_eq!(board.with_move_made(Player::X, spooky(4, 5)), Err(MoveError::SquareOccupied(Move::new(1, 1))));
+        assert_eq!(board.with_move_made(Player::X, spooky(5, 5)), Err(MoveError::SameSquare(Move::new(1, 2))));
+
+        // Closing the cycle 0-1-2 also decides the marks hanging off it.
+        let board = QuantumBoard::from_moves(&[spooky(0, 1), spooky(1, 2), spooky(5, 8), spooky(2, 5), spooky(0, 2)]).unwrap();
+        assert_eq!(board.to_string(), "x1x5 x1o2 o2o4x5\n_ _ x3o4\n_ _ x3");
+        assert_eq!(board.get_active_player(), Some(Player::O));
+        let board = board.with_move_made(Player::O, collapse(0)).unwrap();
+        assert_eq!(board.to_string(), "X5 X1 O2\n_ _ O4\n_ _ X3");
+        assert_eq!(QuantumMove::from_string("collapse to 1 1"), Ok(collapse(4)));
+        assert_eq!(QuantumMove::from_string("0 0 & 2 2"), Ok(spooky(0, 8)));
+    }
+
+    #[test]
+    fn test_simultaneous_lines_score_a_point_and_a_half() {
+        let board = QuantumBoard::from_moves(&[
+            spooky(0, 6), spooky(0, 6), collapse(6),
+            spooky(1, 7), spooky(1, 7), collapse(7),
+            spooky(2, 8), spooky(2, 8),
+        ]).unwrap();
+        let (next_moves, evaluation) = AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(next_moves, vec![collapse(8)]);
+        assert_eq!(evaluation, Evaluation::win(Player::X, 1));
+
+        let board = board.with_move_made(Player::X, collapse(8)).unwrap();
+        assert_eq!(board.get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(board.get_points(Player::X), 1.0);
+        assert_eq!(board.get_points(Player::O), 0.5);
+    }
+
+    #[test]
+    fn test_last_square_takes_a_classical_mark() {
+        let board = QuantumBoard::from_moves(&[
+            spooky(0, 1), spooky(0, 1), collapse(1),
+            spooky(2, 4), spooky(2, 4), collapse(4),
+            spooky(3, 5), spooky(3, 5), collapse(5),
+            spooky(6, 7), spooky(6, 7), collapse(6),
+        ]).unwrap();
+        assert_eq!(board.get_legal_moves(), vec![QuantumMove::Classical(Move::new(2, 2))]);
+        assert_eq!(board.with_move_made(Player::X, QuantumMove::Classical(Move::new(2, 2))).unwrap().get_outcome(), Outcome::Draw);
+    }
+
+    #[test]
+    fn test_small_quantum_positions_are_solved() {
+        let board = QuantumBoard::from_moves(&[spooky(0, 4), spooky(2, 6), spooky(4, 8)]).unwrap();
+        assert_eq!(AlphaBetaSolver::from_board(board).get_evaluation(), Evaluation::draw());
+        let board = board.with_move_made(Player::O, spooky(1, 3)).unwrap();
+        assert_eq!(AlphaBetaSolver::from_board(board).get_evaluation(), Evaluation::win(Player::X, 2));
+    }
+
+}
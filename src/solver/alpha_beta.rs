@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+
+use crate::board::board::{Board, Outcome, Player};
+use crate::game::game::Game;
+use crate::solver::error::SolverError;
+use crate::solver::evaluation::Evaluation;
+
+const WIN_SCORE: i32 = 100;
+
+/// Roughly how much memory the transposition table's entries may take before
+/// it is cleared, so that searches too large to finish soon slow down rather
+/// than run out of memory.
+const MAX_TRANSPOSITION_TABLE_BYTES: usize = 128 << 20;
+
+/// Solves positions of any `Game` (ordinary tic-tac-toe by default) with a
+/// negamax alpha-beta search, without materializing a `Tree`.  Scores inside
+/// the search are `WIN_SCORE - n` for a win `n` plies from the searched
+/// position, the negation of that for a loss and 0 for a draw, so that faster
+/// wins score higher.
+///
+/// Searched positions are cached under their transposition key with the kind
+/// of bound their score is, and with wins and losses counted from the cached
+/// position rather than the root, so they can be reused wherever the position
+/// recurs.  The table starts again from empty whenever it reaches
+/// `MAX_TRANSPOSITION_TABLE_BYTES`.
+pub struct AlphaBetaSolver<G: Game = Board> {
+    board: G,
+    nodes_searched: usize,
+    transposition_table: HashMap<G, (i32, Bound)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+impl<G: Game> AlphaBetaSolver<G> {
+
+    pub fn from_board(board: G) -> Self {
+        AlphaBetaSolver { board, nodes_searched: 0, transposition_table: HashMap::new() }
+    }
+
+    pub fn get_nodes_searched(&self) -> usize {
+        self.nodes_searched
+    }
+
+    pub fn get_evaluation(&mut self) -> Evaluation {
+        let board = self.board.clone();
+        match board.get_active_player() {
+            Some(player) => AlphaBetaSolver::<G>::to_evaluation(self.negamax(&board, player, -WIN_SCORE, WIN_SCORE, 0), player),
+            None => Evaluation::from_outcome(board.get_outcome()),
+        }
+    }
+
+    pub fn get_next_moves(&mut self) -> Result<Vec<G::Move>, SolverError> {
+        let (next_moves, _) = self.get_next_moves_and_evaluation()?;
+        Ok(next_moves)
+    }
+
+    pub fn get_next_moves_and_evaluation(&mut self) -> Result<(Vec<G::Move>, Evaluation), SolverError> {
+        let board = self.board.clone();
+        let active_player = match board.get_active_player() {
+            Some(player) => player,
+            None => return Err(SolverError::GameOver),
+        };
+
+        let mut next_moves = Vec::new();
+        let mut best_score = -WIN_SCORE - 1;
+        for m in board.get_legal_moves().iter() {
+            let child = board.with_legal_move_made(*m);
+            let score = -self.negamax(&child, active_player.other(), -WIN_SCORE, WIN_SCORE, 1);
+            if score > best_score {
+                best_score = score;
+                next_moves.clear();
+                next_moves.push(*m);
+            } else if score == best_score {
+                next_moves.push(*m);
+            }
+        }
+        Ok((next_moves, AlphaBetaSolver::<G>::to_evaluation(best_score, active_player)))
+    }
+
+    /// The line follows the same tie-breaking as `Solver`: among equally good
+    /// moves, the last one in `get_legal_moves()` order is played.
+    pub fn get_evaluation_and_line(&mut self) -> (Evaluation, Vec<G::Move>) {
+        let evaluation = self.get_evaluation();
+        let mut line = Vec::new();
+        let mut board = self.board.clone();
+        while let Some(player) = board.get_active_player() {
+            let score = self.negamax(&board, player, -WIN_SCORE, WIN_SCORE, 0);
+            let best_move = board.get_legal_moves().into_iter().rev()
+                .find(|m| -self.negamax(&board.with_legal_move_made(*m), player.other(), -score - 1, -score + 1, 1) == score)
+                .expect("At least one move achieves the evaluation of the position.");
+            line.push(best_move);
+            board = board.with_legal_move_made(best_move);
+        }
+        (evaluation, line)
+    }
+
+    /// Scores `board`, reached `ply` plies into the search, from `player`'s
+    /// perspective.  The side to move is re-derived from the board, as
+    /// `Solver` does, so positions where the same player has to move twice
+    /// are still scored consistently.
+    fn negamax(&mut self, board: &G, player: Player, mut alpha: i32, mut beta: i32, ply: i32) -> i32 {
+        let active_player = match board.get_active_player() {
+            Some(active_player) => active_player,
+            None => {
+                self.nodes_searched += 1;
+                return match board.get_outcome() {
+                    Outcome::Victory(winner) if winner == player => WIN_SCORE - ply,
+                    Outcome::Victory(_) => -(WIN_SCORE - ply),
+                    Outcome::Draw | Outcome::InProgress | Outcome::Ambiguous => 0,
+                };
+            },
+        };
+        if active_player != player {
+            return -self.negamax(board, active_player, -beta, -alpha, ply);
+        }
+
+        self.nodes_searched += 1;
+        let fastest_win = WIN_SCORE - ply - 1;
+        if fastest_win <= alpha {
+            return fastest_win;
+        }
+        beta = beta.min(fastest_win);
+        let key = board.get_transposition_key();
+        if let Some((score, bound)) = self.transposition_table.get(&key) {
+            let score = AlphaBetaSolver::<G>::from_node_score(*score, ply);
+            match bound {
+                Bound::Exact => return score,
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                Bound::Lower | Bound::Upper => (),
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -WIN_SCORE;
+        for m in board.get_legal_moves() {
+            let score = -self.negamax(&board.with_legal_move_made(m), player.other(), -beta, -alpha, ply + 1);
+            if score > best_score {
+                best_score = score;
+            }
+            if score > alpha {
+                alpha = score;
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        if self.transposition_table.len() >= AlphaBetaSolver::<G>::get_max_transposition_table_entries() {
+            self.transposition_table.clear();
+        }
+        self.transposition_table.insert(key, (AlphaBetaSolver::<G>::to_node_score(best_score, ply), bound));
+        best_score
+    }
+
+    fn get_max_transposition_table_entries() -> usize {
+        MAX_TRANSPOSITION_TABLE_BYTES / std::mem::size_of::<(G, (i32, Bound))>()
+    }
+
+    /// Re-counts a win or loss `ply` plies into the search from the position
+    /// it was found in.
+    fn to_node_score(score: i32, ply: i32) -> i32 {
+        if score > 0 {
+            score + ply
+        } else if score < 0 {
+            score - ply
+        } else {
+            0
+        }
+    }
+
+    fn from_node_score(score: i32, ply: i32) -> i32 {
+        if score > 0 {
+            score - ply
+        } else if score < 0 {
+            score + ply
+        } else {
+            0
+        }
+    }
+
+    fn to_evaluation(score: i32, player: Player) -> Evaluation {
+        if score > 0 {
+            Evaluation::win(player, (WIN_SCORE - score) as usize)
+        } else if score < 0 {
+            Evaluation::win(player.other(), (WIN_SCORE + score) as usize)
+        } else {
+            Evaluation::draw()
+        }
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_alpha_beta {
+    use super::*;
+    use crate::board::board::Move;
+    use crate::board::rules::Rules;
+    use crate::solver::solver::Solver;
+
+    fn assert_matches_tree_solver(board: Board) {
+        let mut solver = Solver::from_board(board);
+        let mut alpha_beta_solver = AlphaBetaSolver::from_board(board);
+        assert_eq!(alpha_beta_solver.get_evaluation(), solver.get_evaluation());
+        assert_eq!(alpha_beta_solver.get_evaluation_and_line(), solver.get_evaluation_and_line());
+        assert_eq!(alpha_beta_solver.get_next_moves_and_evaluation(), solver.get_next_moves_and_evaluation());
+    }
+
+    #[test]
+    fn test_alpha_beta_solver_gets_evaluation() {
+        assert_eq!(
+            AlphaBetaSolver::from_board(Board::empty()).get_evaluation(),
+            Evaluation::draw()
+        );
+
+        assert_eq!(
+            AlphaBetaSolver::from_board(
+                Board::from_position(
+                    "XO_
+                    OO_
+                    XXO",
+                ).unwrap()
+            ).get_evaluation(),
+            Evaluation::win(Player::O, 1)
+        );
+
+        assert_eq!(
+            AlphaBetaSolver::from_board(
+                Board::from_position(
+                    "XOX
+                    OXO
+                    XOX",
+                ).unwrap()
+            ).get_next_moves_and_evaluation(),
+            Err(SolverError::GameOver)
+        );
+    }
+
+    #[test]
+    fn test_alpha_beta_solver_prunes() {
+        let mut alpha_beta_solver = AlphaBetaSolver::from_board(Board::empty());
+        alpha_beta_solver.get_evaluation();
+        assert!(alpha_beta_solver.get_nodes_searched() < 549946);
+    }
+
+    #[test]
+    fn test_alpha_beta_solver_matches_tree_solver() {
+        assert_matches_tree_solver(Board::from_position(
+            "XO_
+            XOX
+            O__"
+        ).unwrap());
+
+        assert_matches_tree_solver(Board::from_position(
+            "XOO
+            _X_
+            ___"
+        ).unwrap());
+
+        assert_matches_tree_solver(Board::from_position(
+            "XO_
+            O__
+            XXO"
+        ).unwrap());
+
+        assert_matches_tree_solver(Board::from_position(
+            "XO__
+            _X__
+            O___"
+        ).unwrap());
+
+        assert_matches_tree_solver(Board::from_position_with_win_length(
+            "XO___
+            _X_O_",
+            2,
+        ).unwrap());
+
+        for first_move in Board::empty().get_legal_moves() {
+            let board = Board::empty().with_move_made(Player::X, first_move).unwrap();
+            for second_move in board.get_legal_moves() {
+                assert_matches_tree_solver(board.with_move_made(Player::O, second_move).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_alpha_beta_solver_solves_larger_boards() {
+        assert_eq!(
+            AlphaBetaSolver::from_board(Board::from_position("____/____/____").unwrap()).get_evaluation(),
+            Evaluation::win(Player::X, 7)
+        );
+        assert_eq!(
+            AlphaBetaSolver::from_board(Board::from_position_with_win_length("____/____/____/____", 4).unwrap()).get_evaluation(),
+            Evaluation::draw()
+        );
+    }
+
+    #[test]
+    fn test_alpha_beta_solver_plays_misere() {
+        let board = Board::empty().with_rules(Rules::misere());
+        assert_eq!(
+            AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation(),
+            Ok((vec![Move::new(1, 1)], Evaluation::draw()))
+        );
+        assert_eq!(
+            AlphaBetaSolver::from_board(
+                Board::from_position(
+                    "XX_
+                    OO_
+                    ___",
+                ).unwrap().with_rules(Rules::misere())
+            ).get_next_moves_and_evaluation(),
+            Ok((vec![Move::new(2, 0), Move::new(2, 1), Move::new(2, 2)], Evaluation::draw()))
+        );
+        for first_move in board.get_legal_moves() {
+            let board = board.with_move_made(Player::X, first_move).unwrap();
+            for second_move in board.get_legal_moves() {
+                assert_matches_tree_solver(board.with_move_made(Player::O, second_move).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_alpha_beta_solver_plays_around_blocked_squares() {
+        let board = Board::from_position("___ _#_ ___").unwrap();
+        assert_matches_tree_solver(board);
+        assert_eq!(AlphaBetaSolver::from_board(board).get_evaluation(), Evaluation::draw());
+
+        let board = Board::from_position("#___/____/____/___#").unwrap();
+        assert_eq!(
+            AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation(),
+            Ok((vec![Move::new(1, 1), Move::new(1, 2), Move::new(2, 1), Move::new(2, 2)], Evaluation::win(Player::X, 5)))
+        );
+    }
+
+    #[test]
+    fn test_alpha_beta_solver_plays_with_gravity() {
+        let board = Board::empty().with_rules(Rules::gravity());
+        assert_matches_tree_solver(board);
+        assert_eq!(
+            AlphaBetaSolver::from_board(Board::from_position_with_win_length("____/____/____/____", 4).unwrap().with_rules(Rules::gravity())).get_evaluation(),
+            Evaluation::draw()
+        );
+
+        let board = Board::from_position_with_win_length(
+            "_____O_
+            _OO_XX_
+            _XXOOO_
+            _XOXXX_
+            OXXOOX_
+            OOXXXOO",
+            4,
+        ).unwrap().with_rules(Rules::gravity());
+        assert_eq!(
+            AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation(),
+            Ok((vec![Move::new(1, 3), Move::new(0, 2), Move::new(0, 4), Move::new(0, 1)], Evaluation::win(Player::X, 7)))
+        );
+    }
+
+}
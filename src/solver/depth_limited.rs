@@ -0,0 +1,204 @@
+use crate::board::board::{Outcome, Player};
+use crate::game::game::Game;
+use crate::solver::error::SolverError;
+use crate::solver::evaluation::Evaluation;
+
+const WIN_SCORE: i32 = 1_000_000;
+
+/// Heuristic scores are clamped to this so they can never be mistaken for a
+/// forced win.
+const MAX_HEURISTIC: i32 = WIN_SCORE / 2;
+
+/// Searches games too large to solve exactly, with a negamax alpha-beta
+/// search that stops `depth` plies below the root and scores positions still
+/// in progress there with `Game::get_heuristic()`.
+///
+/// Wins and losses found within the horizon are exact, and so is a draw when
+/// every line ended before the cut-off; anything else is reported as
+/// `Evaluation::Unknown` with the heuristic score from X's perspective.
+pub struct DepthLimitedSolver<G: Game> {
+    board: G,
+    depth: usize,
+    nodes_searched: usize,
+    is_exact: bool,
+}
+
+impl<G: Game> DepthLimitedSolver<G> {
+
+    pub fn from_board(board: G, depth: usize) -> Self {
+        DepthLimitedSolver { board, depth, nodes_searched: 0, is_exact: true }
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn get_nodes_searched(&self) -> usize {
+        self.nodes_searched
+    }
+
+    pub fn get_evaluation(&mut self) -> Evaluation {
+        let (evaluation, _) = self.get_evaluation_and_line();
+        evaluation
+    }
+
+    pub fn get_next_moves(&mut self) -> Result<Vec<G::Move>, SolverError> {
+        let (next_moves, _) = self.get_next_moves_and_evaluation()?;
+        Ok(next_moves)
+    }
+
+    pub fn get_next_moves_and_evaluation(&mut self) -> Result<(Vec<G::Move>, Evaluation), SolverError> {
+        let board = self.board.clone();
+        let active_player = match board.get_active_player() {
+            Some(player) => player,
+            None => return Err(SolverError::GameOver),
+        };
+
+        self.is_exact = true;
+        let mut next_moves = Vec::new();
+        let mut best_score = -WIN_SCORE - 1;
+        let mut line = Vec::new();
+        for m in board.get_legal_moves() {
+            let child = board.with_legal_move_made(m);
+            let score = -self.negamax(&child, active_player.other(), self.depth.saturating_sub(1), -WIN_SCORE, WIN_SCORE, 1, &mut line);
+            if score > best_score {
+                best_score = score;
+                next_moves.clear();
+                next_moves.push(m);
+            } else if score == best_score {
+                next_moves.push(m);
+            }
+        }
+        Ok((next_moves, self.to_evaluation(best_score, active_player)))
+    }
+
+    /// The principal variation: the line both sides play if the heuristic
+    /// scores at the horizon were the true values.  It ends at the horizon
+    /// or the end of the game, whichever comes first.
+    pub fn get_evaluation_and_line(&mut self) -> (Evaluation, Vec<G::Move>) {
+        let board = self.board.clone();
+        let player = match board.get_active_player() {
+            Some(player) => player,
+            None => return (Evaluation::from_outcome(board.get_outcome()), Vec::new()),
+        };
+        self.is_exact = true;
+        let mut line = Vec::new();
+        let score = self.negamax(&board, player, self.depth, -WIN_SCORE, WIN_SCORE, 0, &mut line);
+        (self.to_evaluation(score, player), line)
+    }
+
+    /// Scores `board` from `player`'s perspective, searching `depth` more
+    /// plies, and fills `line` with the best continuation found.
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(&mut self, board: &G, player: Player, depth: usize, mut alpha: i32, beta: i32, ply: i32, line: &mut Vec<G::Move>) -> i32 {
+        self.nodes_searched += 1;
+        line.clear();
+        let active_player = match board.get_active_player() {
+            Some(active_player) => active_player,
+            None => {
+                return match board.get_outcome() {
+                    Outcome::Victory(winner) if winner == player => WIN_SCORE - ply,
+                    Outcome::Victory(_) => -(WIN_SCORE - ply),
+                    Outcome::Draw | Outcome::InProgress | Outcome::Ambiguous => 0,
+                };
+            },
+        };
+        if active_player != player {
+            return -self.negamax(board, active_player, depth, -beta, -alpha, ply, line);
+        }
+        if depth == 0 {
+            self.is_exact = false;
+            let heuristic = board.get_heuristic().clamp(-MAX_HEURISTIC, MAX_HEURISTIC);
+            return match player {
+                Player::X => heuristic,
+                Player::O => -heuristic,
+            };
+        }
+
+        let mut best_score = -WIN_SCORE;
+        let mut child_line = Vec::new();
+        for m in board.get_legal_moves() {
+            let score = -self.negamax(&board.with_legal_move_made(m), player.other(), depth - 1, -beta, -alpha, ply + 1, &mut child_line);
+            if score > best_score {
+                best_score = score;
+                line.clear();
+                line.push(m);
+                line.append(&mut child_line);
+            }
+            if score > alpha {
+                alpha = score;
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        best_score
+    }
+
+    fn to_evaluation(&self, score: i32, player: Player) -> Evaluation {
+        if score > MAX_HEURISTIC {
+            Evaluation::win(player, (WIN_SCORE - score) as usize)
+        } else if score < -MAX_HEURISTIC {
+            Evaluation::win(player.other(), (WIN_SCORE + score) as usize)
+        } else if self.is_exact {
+            Evaluation::draw()
+        } else {
+            match player {
+                Player::X => Evaluation::unknown(score),
+                Player::O => Evaluation::unknown(-score),
+            }
+        }
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_depth_limited {
+    use super::*;
+    use crate::board::board::{Board, Move};
+    use crate::board::rules::Rules;
+    use crate::board::shape::BoardShape;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+
+    #[test]
+    fn test_depth_limited_solver_is_exact_beyond_the_end_of_the_game() {
+        let board = Board::from_position(
+            "XO_
+            _X_
+            O__",
+        ).unwrap();
+        let mut solver = DepthLimitedSolver::from_board(board, 9);
+        assert_eq!(solver.get_next_moves_and_evaluation(), AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation());
+        assert_eq!(DepthLimitedSolver::from_board(Board::empty(), 9).get_evaluation(), Evaluation::draw());
+    }
+
+    #[test]
+    fn test_depth_limited_solver_finds_wins_within_the_horizon() {
+        let board = Board::from_position(
+            "XX_
+            OO_
+            ___",
+        ).unwrap();
+        let (evaluation, line) = DepthLimitedSolver::from_board(board, 1).get_evaluation_and_line();
+        assert_eq!(evaluation, Evaluation::win(Player::X, 1));
+        assert_eq!(line, vec![Move::new(0, 2)]);
+        assert_eq!(DepthLimitedSolver::from_board(Board::empty(), 2).get_evaluation(), Evaluation::unknown(1));
+    }
+
+    #[test]
+    fn test_depth_limited_solver_searches_connect_four() {
+        let board = Board::from_position_with_win_length(
+            "_______/_______/_______/_______/__OO___/__XXX__",
+            4,
+        ).unwrap().with_rules(Rules::gravity());
+        let mut solver = DepthLimitedSolver::from_board(board, 3);
+        assert_eq!(solver.get_evaluation(), Evaluation::win(Player::X, 2));
+
+        let empty = Board::empty_with_shape(BoardShape::new(7, 6, 4).unwrap()).with_rules(Rules::gravity());
+        let (next_moves, evaluation) = DepthLimitedSolver::from_board(empty, 4).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(next_moves, vec![Move::new(5, 3)]);
+        assert!(!evaluation.is_solved());
+    }
+
+}
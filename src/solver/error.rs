@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::board::error::{MoveError, ParseError};
+use crate::board::rules::Rules;
+use crate::board::shape::BoardShape;
+use crate::board::validation::Illegality;
+
+/// Errors from asking a solver or tablebase about a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolverError {
+    GameOver,
+    IllegalPosition(Illegality),
+    Move(MoveError),
+    Tablebase(TablebaseError),
+    UnsupportedShape(BoardShape),
+    UnsupportedRules(Rules),
+    UnsupportedBlockedSquares,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolverError::GameOver => write!(f, "The game is already over."),
+            SolverError::IllegalPosition(illegality) => write!(f, "This position can't arise in a real game. {}.", illegality),
+            SolverError::Move(error) => write!(f, "{}", error),
+            SolverError::Tablebase(error) => write!(f, "{}", error),
+            SolverError::UnsupportedShape(shape) => write!(f, "Only standard 3x3 positions are supported, not {}.", shape.to_string()),
+            SolverError::UnsupportedRules(rules) => write!(f, "Only positions under standard rules are supported, not {} rules.", rules.to_string()),
+            SolverError::UnsupportedBlockedSquares => write!(f, "Only positions without blocked squares are supported."),
+        }
+    }
+}
+
+impl Error for SolverError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SolverError::GameOver | SolverError::UnsupportedShape(_) | SolverError::UnsupportedRules(_) | SolverError::UnsupportedBlockedSquares => None,
+            SolverError::IllegalPosition(illegality) => Some(illegality),
+            SolverError::Move(error) => Some(error),
+            SolverError::Tablebase(error) => Some(error),
+        }
+    }
+}
+
+impl From<Illegality> for SolverError {
+    fn from(illegality: Illegality) -> Self {
+        SolverError::IllegalPosition(illegality)
+    }
+}
+
+impl From<MoveError> for SolverError {
+    fn from(error: MoveError) -> Self {
+        SolverError::Move(error)
+    }
+}
+
+impl From<TablebaseError> for SolverError {
+    fn from(error: TablebaseError) -> Self {
+        SolverError::Tablebase(error)
+    }
+}
+
+/// Errors from reading or writing tablebase files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TablebaseError {
+    Io { path: String, kind: io::ErrorKind },
+    NotATablebase,
+    UnsupportedVersion { version: u8, expected: u8 },
+    WrongEntryCount { n_entries: usize, expected: usize },
+    WrongLength { length: usize, expected: usize },
+    InvalidEntry(usize),
+    InvalidBestMoves(ParseError),
+}
+
+impl fmt::Display for TablebaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TablebaseError::Io { path, kind } => write!(f, "Could not access tablebase file {}: {}", path, io::Error::from(*kind)),
+            TablebaseError::NotATablebase => write!(f, "Not a tablebase file"),
+            TablebaseError::UnsupportedVersion { version, expected } => write!(f, "Unsupported tablebase version {}, expected {}", version, expected),
+            TablebaseError::WrongEntryCount { n_entries, expected } => write!(f, "Tablebase should have {} entries but has {}", expected, n_entries),
+            TablebaseError::WrongLength { length, expected } => write!(f, "Tablebase should be {} bytes long but is {}", expected, length),
+            TablebaseError::InvalidEntry(legal_index) => write!(f, "Invalid value in tablebase entry {}", legal_index),
+            TablebaseError::InvalidBestMoves(error) => write!(f, "Invalid best moves in tablebase: {}", error),
+        }
+    }
+}
+
+impl Error for TablebaseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TablebaseError::InvalidBestMoves(error) => Some(error),
+            _ => None,
+        }
+    }
+}
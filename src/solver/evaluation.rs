@@ -0,0 +1,206 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::board::{Outcome, Player};
+use crate::board::error::ParseError;
+
+/// The exact game-theoretic value of a position, or a heuristic score when the
+/// position hasn't been solved.
+///
+/// Evaluations are ordered from X's perspective: O winning sorts lowest, X
+/// winning highest.  Among wins, the faster one is better for the winner, so
+/// the winning side hurries and the losing side resists.  Unknown positions
+/// sort by their heuristic score (positive favours X), with a known draw
+/// placed just above `Unknown(0)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Evaluation {
+    Win { player: Player, n_plies: usize },
+    Draw,
+    Unknown(i32),
+}
+
+impl Evaluation {
+
+    pub fn win(player: Player, n_plies: usize) -> Self {
+        Evaluation::Win { player, n_plies }
+    }
+
+    pub fn draw() -> Self {
+        Evaluation::Draw
+    }
+
+    pub fn unknown(score: i32) -> Self {
+        Evaluation::Unknown(score)
+    }
+
+    /// The evaluation of a finished game, where any win took zero plies.
+    /// Games in progress have no evaluation of their own and get `Unknown(0)`.
+    pub fn from_outcome(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Victory(player) => Evaluation::win(player, 0),
+            Outcome::Draw => Evaluation::draw(),
+            Outcome::InProgress | Outcome::Ambiguous => Evaluation::unknown(0),
+        }
+    }
+
+    pub fn get_winner(&self) -> Option<Player> {
+        match self {
+            Evaluation::Win { player, .. } => Some(*player),
+            Evaluation::Draw | Evaluation::Unknown(_) => None,
+        }
+    }
+
+    pub fn get_n_plies(&self) -> Option<usize> {
+        match self {
+            Evaluation::Win { n_plies, .. } => Some(*n_plies),
+            Evaluation::Draw | Evaluation::Unknown(_) => None,
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Evaluation::Unknown(_))
+    }
+
+    /// The evaluation as seen one ply earlier, i.e. from the parent position.
+    pub fn with_ply_added(&self) -> Self {
+        match self {
+            Evaluation::Win { player, n_plies } => Evaluation::win(*player, n_plies + 1),
+            Evaluation::Draw | Evaluation::Unknown(_) => *self,
+        }
+    }
+
+    /// Describes the evaluation in terms of the first and second player, for
+    /// variants where players don't own a symbol.
+    pub fn to_string_by_turn_order(&self) -> String {
+        self.to_string_with_player_names("First player", "Second player")
+    }
+
+    /// Describes the evaluation with the players called `x_name` and
+    /// `o_name` instead of X and O.
+    pub fn to_string_with_player_names(&self, x_name: &str, o_name: &str) -> String {
+        let describe_player = |player: &Player| match player {
+            Player::X => x_name,
+            Player::O => o_name,
+        };
+        match self {
+            Evaluation::Win { player, n_plies: 0 } => format!("{} has won", describe_player(player)),
+            Evaluation::Win { player, n_plies } => format!("{} wins in {}", describe_player(player), n_plies),
+            _ => self.to_string(),
+        }
+    }
+
+    fn sort_key(&self) -> (i8, i64, i8) {
+        match self {
+            Evaluation::Win { player: Player::O, n_plies } => (-1, *n_plies as i64, 0),
+            Evaluation::Unknown(score) => (0, *score as i64, 0),
+            Evaluation::Draw => (0, 0, 1),
+            Evaluation::Win { player: Player::X, n_plies } => (1, -(*n_plies as i64), 0),
+        }
+    }
+
+}
+
+impl Ord for Evaluation {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Evaluation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Evaluation::Win { player, n_plies: 0 } => write!(f, "{} has won", player.to_string()),
+            Evaluation::Win { player, n_plies } => write!(f, "{} wins in {}", player.to_string(), n_plies),
+            Evaluation::Draw => write!(f, "Drawn"),
+            Evaluation::Unknown(score) => write!(f, "Unknown ({:+})", score),
+        }
+    }
+}
+
+impl FromStr for Evaluation {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || ParseError::InvalidEvaluation(s.to_string());
+        if s == "Drawn" {
+            return Ok(Evaluation::Draw);
+        }
+        if let Some(score) = s.strip_prefix("Unknown (").and_then(|rest| rest.strip_suffix(')')) {
+            return score.parse::<i32>().map(Evaluation::Unknown).map_err(|_| invalid());
+        }
+        let player = match s.get(..2) {
+            Some("X ") => Player::X,
+            Some("O ") => Player::O,
+            _ => return Err(invalid()),
+        };
+        match &s[2..] {
+            "has won" => Ok(Evaluation::win(player, 0)),
+            rest => match rest.strip_prefix("wins in ").map(|n| n.parse::<usize>()) {
+                Some(Ok(n_plies)) if n_plies > 0 => Ok(Evaluation::win(player, n_plies)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test_evaluation {
+    use super::*;
+
+    #[test]
+    fn test_evaluations_sort_from_x_perspective() {
+        let mut evaluations = vec![
+            Evaluation::draw(),
+            Evaluation::win(Player::X, 5),
+            Evaluation::unknown(-3),
+            Evaluation::win(Player::O, 2),
+            Evaluation::win(Player::X, 1),
+            Evaluation::unknown(0),
+            Evaluation::win(Player::O, 4),
+            Evaluation::unknown(7),
+        ];
+        evaluations.sort();
+        assert_eq!(
+            evaluations,
+            vec![
+                Evaluation::win(Player::O, 2),
+                Evaluation::win(Player::O, 4),
+                Evaluation::unknown(-3),
+                Evaluation::unknown(0),
+                Evaluation::draw(),
+                Evaluation::unknown(7),
+                Evaluation::win(Player::X, 5),
+                Evaluation::win(Player::X, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluation_round_trips_through_strings() {
+        let evaluations = vec![
+            Evaluation::win(Player::X, 0),
+            Evaluation::win(Player::O, 3),
+            Evaluation::draw(),
+            Evaluation::unknown(-12),
+            Evaluation::unknown(0),
+            Evaluation::unknown(4),
+        ];
+        for evaluation in evaluations {
+            assert_eq!(evaluation.to_string().parse::<Evaluation>(), Ok(evaluation));
+        }
+        assert_eq!(Evaluation::win(Player::X, 3).to_string(), "X wins in 3".to_string());
+        assert_eq!(Evaluation::unknown(4).to_string(), "Unknown (+4)".to_string());
+        assert_eq!("Ambiguous".parse::<Evaluation>(), Err(ParseError::InvalidEvaluation("Ambiguous".to_string())));
+        assert!("X wins in 0".parse::<Evaluation>().is_err());
+    }
+
+}
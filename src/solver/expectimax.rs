@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::board::board::{Board, Move, Outcome, Player};
+use crate::board::validation::Illegality;
+use crate::solver::error::SolverError;
+use crate::solver::probability::WinProbabilities;
+
+/// A position in random-turn play: either a fair coin is about to decide who
+/// moves next, or it has, and `Player` places a mark.
+#[derive(Debug, Copy, Clone)]
+enum Node {
+    Chance(Board),
+    Decision(Board, Player),
+}
+
+/// Solves random-turn tic-tac-toe, where a coin toss before every move
+/// decides who makes it, so the mark counts say nothing about whose turn it
+/// is and any mix of marks can come up.
+///
+/// Chance nodes average the chances after each player wins the toss, and
+/// decision nodes take the move that is best for the player moving, by
+/// expected score and then by the chance of winning outright.  The results
+/// are exact probabilities, cached for each position at a chance node.
+pub struct ExpectimaxSolver {
+    board: Board,
+    probabilities: HashMap<Board, WinProbabilities>,
+}
+
+impl ExpectimaxSolver {
+
+    /// Positions where both players have a line can't come up, since the
+    /// game ends at the first line.
+    pub fn from_board(board: Board) -> Result<Self, SolverError> {
+        if board.get_outcome() == Outcome::Ambiguous {
+            return Err(SolverError::IllegalPosition(Illegality::BothPlayersWon));
+        }
+        Ok(ExpectimaxSolver { board, probabilities: HashMap::new() })
+    }
+
+    /// The chances before the coin is tossed for the next move.
+    pub fn get_win_probabilities(&mut self) -> WinProbabilities {
+        self.get_probabilities_of(Node::Chance(self.board))
+    }
+
+    /// The chances after each legal move, if `player` wins the toss, best
+    /// move for `player` first.
+    pub fn get_move_probabilities(&mut self, player: Player) -> Result<Vec<(Move, WinProbabilities)>, SolverError> {
+        if self.board.get_outcome() != Outcome::InProgress {
+            return Err(SolverError::GameOver);
+        }
+        let mut move_probabilities = self.board.get_legal_moves().into_iter()
+            .map(|m| (m, self.get_probabilities_of(Node::Chance(ExpectimaxSolver::with_mark_placed(&self.board, player, m)))))
+            .collect::<Vec<(Move, WinProbabilities)>>();
+        move_probabilities.sort_by(|(_, probabilities), (_, other)| other.compare(probabilities, player));
+        Ok(move_probabilities)
+    }
+
+    fn get_probabilities_of(&mut self, node: Node) -> WinProbabilities {
+        match node {
+            Node::Chance(board) => {
+                match board.get_outcome() {
+                    Outcome::Victory(player) => return WinProbabilities::win(player),
+                    Outcome::Draw => return WinProbabilities::draw(),
+                    Outcome::InProgress | Outcome::Ambiguous => (),
+                }
+                if let Some(probabilities) = self.probabilities.get(&board) {
+                    return *probabilities;
+                }
+                let probabilities = self.get_probabilities_of(Node::Decision(board, Player::X))
+                    .averaged_with(self.get_probabilities_of(Node::Decision(board, Player::O)));
+                self.probabilities.insert(board, probabilities);
+                probabilities
+            },
+            Node::Decision(board, player) => {
+                board.get_legal_moves().into_iter()
+                    .map(|m| self.get_probabilities_of(Node::Chance(ExpectimaxSolver::with_mark_placed(&board, player, m))))
+                    .max_by(|probabilities, other| probabilities.compare(other, player))
+                    .expect("Games in progress have at least one legal move.")
+            },
+        }
+    }
+
+    fn with_mark_placed(board: &Board, player: Player, m: Move) -> Board {
+        let mut board = *board;
+        board.place_mark(player, m).expect("Move is legal by definition of get_legal_moves().");
+        board
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_expectimax {
+    use super::*;
+    use crate::solver::probability::Probability;
+
+    #[test]
+    fn test_expectimax_solver_gives_exact_chances_from_the_empty_board() {
+        let mut solver = ExpectimaxSolver::from_board(Board::empty()).unwrap();
+        let probabilities = solver.get_win_probabilities();
+        assert_eq!(probabilities.get_win(Player::X), Probability::new(123, 256));
+        assert_eq!(probabilities.get_win(Player::O), Probability::new(123, 256));
+        assert_eq!(probabilities.get_draw(), Probability::new(5, 128));
+        let (best_move, after_best_move) = solver.get_move_probabilities(Player::O).unwrap()[0];
+        assert_eq!(best_move, Move::new(1, 1));
+        assert_eq!(after_best_move.get_win(Player::O), Probability::new(165, 256));
+    }
+
+    #[test]
+    fn test_expectimax_solver_ignores_mark_counts() {
+        let board = Board::from_position("XX_ OO_ ___").unwrap();
+        let half = Probability::new(1, 2);
+        let mut solver = ExpectimaxSolver::from_board(board).unwrap();
+        assert_eq!(solver.get_win_probabilities(), WinProbabilities::new(half, half, Probability::zero()));
+        assert_eq!(solver.get_move_probabilities(Player::O).unwrap()[0], (Move::new(1, 2), WinProbabilities::win(Player::O)));
+
+        let board = Board::from_position("X_X _O_ X_O").unwrap();
+        let probabilities = ExpectimaxSolver::from_board(board).unwrap().get_win_probabilities();
+        assert_eq!(probabilities.get_win(Player::X), Probability::new(3, 4));
+    }
+
+    #[test]
+    fn test_expectimax_solver_plays_around_blocked_squares() {
+        let board = Board::from_position("XX# OO_ ___").unwrap();
+        let mut solver = ExpectimaxSolver::from_board(board).unwrap();
+        assert_eq!(solver.get_win_probabilities().get_win(Player::O), Probability::new(9, 16));
+        let move_probabilities = solver.get_move_probabilities(Player::X).unwrap();
+        assert_eq!(move_probabilities.len(), 4);
+        assert_eq!(move_probabilities[0].0, Move::new(1, 2));
+        assert_eq!(move_probabilities[0].1.get_draw(), Probability::new(3, 4));
+    }
+
+    #[test]
+    fn test_expectimax_solver_rejects_finished_games() {
+        let board = Board::from_position("XXX OOO ___").unwrap();
+        assert!(matches!(ExpectimaxSolver::from_board(board), Err(SolverError::IllegalPosition(Illegality::BothPlayersWon))));
+        let board = Board::from_position("XXX OO_ ___").unwrap();
+        let mut solver = ExpectimaxSolver::from_board(board).unwrap();
+        assert_eq!(solver.get_win_probabilities(), WinProbabilities::win(Player::X));
+        assert_eq!(solver.get_move_probabilities(Player::O), Err(SolverError::GameOver));
+    }
+
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use crate::board::board::{Outcome, Player};
+use crate::game::game::Game;
+use crate::solver::error::SolverError;
+use crate::solver::evaluation::Evaluation;
+
+/// Proves forced wins in games whose positions can recur and that are too
+/// large for `RetrogradeSolver`, by asking whether either player can force a
+/// win within 1, 2, 3... plies, up to `max_depth`.
+///
+/// Each question has a bounded depth, so cycles can't make the search run
+/// forever, and the first depth with an answer is the fastest win.  Answers
+/// are cached under the position's transposition key, which may leave out
+/// whose turn it is, so they're kept for the player to move and for their
+/// opponent rather than for X and O: the fewest plies each has been shown to
+/// win within, and the most they have been shown not to.  Positions where neither player can force a win within
+/// `max_depth` plies are `Evaluation::Unknown(0)`.
+pub struct IterativeDeepeningSolver<G: Game> {
+    board: G,
+    max_depth: usize,
+    nodes_searched: usize,
+    proofs: HashMap<(G, bool), (usize, usize)>,
+}
+
+impl<G: Game> IterativeDeepeningSolver<G> {
+
+    pub fn from_board(board: G, max_depth: usize) -> Self {
+        IterativeDeepeningSolver { board, max_depth, nodes_searched: 0, proofs: HashMap::new() }
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn get_nodes_searched(&self) -> usize {
+        self.nodes_searched
+    }
+
+    pub fn get_evaluation(&mut self) -> Evaluation {
+        let board = self.board.clone();
+        self.get_evaluation_of(&board, self.max_depth)
+    }
+
+    pub fn get_next_moves(&mut self) -> Result<Vec<G::Move>, SolverError> {
+        let (next_moves, _) = self.get_next_moves_and_evaluation()?;
+        Ok(next_moves)
+    }
+
+    pub fn get_next_moves_and_evaluation(&mut self) -> Result<(Vec<G::Move>, Evaluation), SolverError> {
+        let board = self.board.clone();
+        self.get_next_moves_and_evaluation_from(&board, self.max_depth)
+    }
+
+    /// The line follows the last of the best moves, like `AlphaBetaSolver`,
+    /// and stops at the end of the game or after `max_depth` plies.
+    pub fn get_evaluation_and_line(&mut self) -> (Evaluation, Vec<G::Move>) {
+        let mut board = self.board.clone();
+        let evaluation = self.get_evaluation_of(&board, self.max_depth);
+        let mut line = Vec::new();
+        while line.len() < self.max_depth {
+            let next_moves = match self.get_next_moves_and_evaluation_from(&board, self.max_depth - line.len()) {
+                Ok((next_moves, _)) => next_moves,
+                Err(_) => break,
+            };
+            let best_move = *next_moves.last().expect("Games in progress have at least one legal move.");
+            line.push(best_move);
+            board = board.with_legal_move_made(best_move);
+        }
+        (evaluation, line)
+    }
+
+    fn get_evaluation_of(&mut self, board: &G, max_depth: usize) -> Evaluation {
+        let active_player = match board.get_active_player() {
+            Some(player) => player,
+            None => return Evaluation::from_outcome(board.get_outcome()),
+        };
+        for depth in 1..=max_depth {
+            for player in [active_player, active_player.other()] {
+                if self.can_win_within(board, player, depth) {
+                    return Evaluation::win(player, depth);
+                }
+            }
+        }
+        Evaluation::unknown(0)
+    }
+
+    /// Once the evaluation is known, the best moves are the ones that win
+    /// fastest, lose slowest, or avoid a loss within the horizon, which only
+    /// takes one more proof per move.
+    fn get_next_moves_and_evaluation_from(&mut self, board: &G, max_depth: usize) -> Result<(Vec<G::Move>, Evaluation), SolverError> {
+        let active_player = match board.get_active_player() {
+            Some(player) => player,
+            None => return Err(SolverError::GameOver),
+        };
+        let evaluation = self.get_evaluation_of(board, max_depth);
+        let mut next_moves = Vec::new();
+        for m in board.get_legal_moves() {
+            let child = board.with_legal_move_made(m);
+            let is_best = match (evaluation.get_winner(), evaluation.get_n_plies()) {
+                (Some(winner), Some(n_plies)) if winner == active_player => self.can_win_within(&child, winner, n_plies - 1),
+                (Some(winner), Some(n_plies)) => n_plies == 1 || !self.can_win_within(&child, winner, n_plies - 2),
+                _ => !self.can_win_within(&child, active_player.other(), max_depth.saturating_sub(1)),
+            };
+            if is_best {
+                next_moves.push(m);
+            }
+        }
+        Ok((next_moves, evaluation))
+    }
+
+    /// Whether `player` can force a win within `depth` plies, whoever is to
+    /// move.
+    fn can_win_within(&mut self, board: &G, player: Player, depth: usize) -> bool {
+        self.nodes_searched += 1;
+        let active_player = match board.get_active_player() {
+            Some(active_player) => active_player,
+            None => return board.get_outcome() == Outcome::Victory(player),
+        };
+        if depth == 0 {
+            return false;
+        }
+        let key = (board.get_transposition_key(), active_player == player);
+        if let Some((proved, disproved)) = self.proofs.get(&key) {
+            if *proved <= depth {
+                return true;
+            }
+            if *disproved >= depth {
+                return false;
+            }
+        }
+
+        let moves = board.get_legal_moves();
+        let can_win = match active_player == player {
+            true => moves.into_iter().any(|m| self.can_win_within(&board.with_legal_move_made(m), player, depth - 1)),
+            false => moves.into_iter().all(|m| self.can_win_within(&board.with_legal_move_made(m), player, depth - 1)),
+        };
+        let (proved, disproved) = self.proofs.entry(key).or_insert((usize::MAX, 0));
+        match can_win {
+            true => *proved = (*proved).min(depth),
+            false => *disproved = (*disproved).max(depth),
+        }
+        can_win
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_iterative_deepening {
+    use super::*;
+    use crate::board::board::Board;
+    use crate::notakto::notakto::Notakto;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+
+    #[test]
+    fn test_iterative_deepening_solver_proves_the_same_wins_as_alpha_beta() {
+        let board = Board::empty();
+        for first_move in board.get_legal_moves() {
+            let board = board.with_move_made(Player::X, first_move).unwrap();
+            for second_move in board.get_legal_moves() {
+                let board = board.with_move_made(Player::O, second_move).unwrap();
+                let mut alpha_beta_solver = AlphaBetaSolver::from_board(board);
+                let (next_moves, evaluation) = alpha_beta_solver.get_next_moves_and_evaluation().unwrap();
+                let mut solver = IterativeDeepeningSolver::from_board(board, 9);
+                match evaluation {
+                    Evaluation::Win { .. } => assert_eq!(solver.get_next_moves_and_evaluation(), Ok((next_moves, evaluation))),
+                    _ => assert_eq!(solver.get_evaluation(), Evaluation::unknown(0)),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_solver_handles_keys_without_the_player_to_move() {
+        let game = Notakto::from_positions(&["X_______X", "_________"]).unwrap();
+        let evaluation = AlphaBetaSolver::from_board(game.clone()).get_evaluation();
+        assert_eq!(evaluation, Evaluation::win(Player::X, 10));
+        assert_eq!(IterativeDeepeningSolver::from_board(game, 10).get_evaluation(), evaluation);
+    }
+
+    #[test]
+    fn test_iterative_deepening_solver_stops_at_its_horizon() {
+        let board = Board::from_position("X__ ___ _O_").unwrap();
+        assert_eq!(IterativeDeepeningSolver::from_board(board, 2).get_evaluation(), Evaluation::unknown(0));
+        assert_eq!(IterativeDeepeningSolver::from_board(board, 9).get_evaluation_and_line(), AlphaBetaSolver::from_board(board).get_evaluation_and_line());
+    }
+
+}
@@ -1 +1,12 @@
-pub mod solver;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod solver;
+pub mod error;
+pub mod evaluation;
+pub mod probability;
+pub mod transposition_table;
+pub mod alpha_beta;
+pub mod depth_limited;
+pub mod retrograde;
+pub mod iterative_deepening;
+pub mod expectimax;
+pub mod tablebase;
\ No newline at end of file
@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+
+use crate::board::board::Player;
+
+/// An exact probability, stored as a fraction in lowest terms.
+///
+/// The only chance in the games solved here is a fair coin, so every
+/// probability is a whole number of halves, quarters, eighths and so on, and
+/// fractions stay exact where floats would drift.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Probability {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl Probability {
+
+    pub fn new(numerator: u64, denominator: u64) -> Self {
+        assert!(denominator > 0 && numerator <= denominator, "Probabilities are fractions between 0 and 1.");
+        let divisor = Probability::gcd(numerator, denominator);
+        Probability { numerator: numerator / divisor, denominator: denominator / divisor }
+    }
+
+    pub fn zero() -> Self {
+        Probability::new(0, 1)
+    }
+
+    pub fn one() -> Self {
+        Probability::new(1, 1)
+    }
+
+    pub fn get_numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    pub fn get_denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    /// The chance of either this or `other` happening, for events that can't
+    /// both happen.
+    pub fn plus(&self, other: Probability) -> Self {
+        let sum = self.plus_unchecked(other);
+        Probability::new(sum.numerator, sum.denominator)
+    }
+
+    /// The chance of an event that follows this one or `other` on the toss
+    /// of a fair coin.
+    pub fn averaged_with(&self, other: Probability) -> Self {
+        let sum = self.plus_unchecked(other);
+        Probability::new(sum.numerator, 2 * sum.denominator)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Like "3/8 (37.5%)", or "1 (100.0%)" for certainties.
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        if self.denominator == 1 {
+            return format!("{} ({:.1}%)", self.numerator, 100.0 * self.to_f64());
+        }
+        format!("{}/{} ({:.1}%)", self.numerator, self.denominator, 100.0 * self.to_f64())
+    }
+
+    /// The sum may exceed 1 before it is halved, so it isn't a probability.
+    fn plus_unchecked(&self, other: Probability) -> Probability {
+        let denominator = Probability::lcm(self.denominator, other.denominator);
+        let numerator = self.numerator * (denominator / self.denominator) + other.numerator * (denominator / other.denominator);
+        Probability { numerator, denominator }
+    }
+
+    fn gcd(a: u64, b: u64) -> u64 {
+        match b {
+            0 => a,
+            _ => Probability::gcd(b, a % b),
+        }
+    }
+
+    fn lcm(a: u64, b: u64) -> u64 {
+        a / Probability::gcd(a, b) * b
+    }
+
+}
+
+impl Ord for Probability {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator as u128 * other.denominator as u128).cmp(&(other.numerator as u128 * self.denominator as u128))
+    }
+}
+
+impl PartialOrd for Probability {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The chances of each result of a game where chance decides who moves, with
+/// both players playing to maximise their expected score.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WinProbabilities {
+    x_win: Probability,
+    o_win: Probability,
+    draw: Probability,
+}
+
+impl WinProbabilities {
+
+    pub fn new(x_win: Probability, o_win: Probability, draw: Probability) -> Self {
+        assert_eq!(x_win.plus(o_win).plus(draw), Probability::one(), "The results of a game are exhaustive.");
+        WinProbabilities { x_win, o_win, draw }
+    }
+
+    pub fn win(player: Player) -> Self {
+        match player {
+            Player::X => WinProbabilities::new(Probability::one(), Probability::zero(), Probability::zero()),
+            Player::O => WinProbabilities::new(Probability::zero(), Probability::one(), Probability::zero()),
+        }
+    }
+
+    pub fn draw() -> Self {
+        WinProbabilities::new(Probability::zero(), Probability::zero(), Probability::one())
+    }
+
+    pub fn get_win(&self, player: Player) -> Probability {
+        match player {
+            Player::X => self.x_win,
+            Player::O => self.o_win,
+        }
+    }
+
+    pub fn get_draw(&self) -> Probability {
+        self.draw
+    }
+
+    /// The chances when a fair coin decides between this and `other`.
+    pub fn averaged_with(&self, other: WinProbabilities) -> Self {
+        WinProbabilities::new(
+            self.x_win.averaged_with(other.x_win),
+            self.o_win.averaged_with(other.o_win),
+            self.draw.averaged_with(other.draw),
+        )
+    }
+
+    /// Orders the chances by how good they are for `player`: by expected
+    /// score, counting a draw as half a win, and then by the chance of
+    /// winning outright.
+    pub fn compare(&self, other: &WinProbabilities, player: Player) -> Ordering {
+        // A win and a draw are worth 2 and 1, so comparing 2 * win + draw is
+        // comparing win - loss, since the three chances add up to 1.
+        let score = |probabilities: &WinProbabilities| probabilities.get_win(player).plus_unchecked(probabilities.get_win(player)).plus_unchecked(probabilities.draw);
+        score(self).cmp(&score(other))
+            .then_with(|| self.get_win(player).cmp(&other.get_win(player)))
+    }
+
+    /// Like "X wins 3/8 (37.5%), O wins 3/8 (37.5%), draw 1/4 (25.0%)".
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("X wins {}, O wins {}, draw {}", self.x_win.to_string(), self.o_win.to_string(), self.draw.to_string())
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_probability {
+    use super::*;
+
+    #[test]
+    fn test_probability_arithmetic_is_exact() {
+        assert_eq!(Probability::new(2, 8), Probability::new(1, 4));
+        assert_eq!(Probability::new(1, 4).plus(Probability::new(1, 8)), Probability::new(3, 8));
+        assert_eq!(Probability::one().averaged_with(Probability::new(1, 4)), Probability::new(5, 8));
+        assert!(Probability::new(1, 3) < Probability::new(3, 8));
+        assert_eq!(Probability::new(3, 8).to_string(), "3/8 (37.5%)");
+        assert_eq!(Probability::zero().to_string(), "0 (0.0%)");
+    }
+
+    #[test]
+    fn test_win_probabilities_prefer_expected_score_then_winning() {
+        let half = Probability::new(1, 2);
+        let gamble = WinProbabilities::new(half, half, Probability::zero());
+        let draw = WinProbabilities::draw();
+        assert_eq!(gamble.compare(&draw, Player::X), Ordering::Greater);
+        assert_eq!(gamble.compare(&draw, Player::O), Ordering::Greater);
+        assert_eq!(WinProbabilities::win(Player::O).compare(&gamble, Player::X), Ordering::Less);
+        assert_eq!(WinProbabilities::win(Player::X).averaged_with(draw), WinProbabilities::new(half, Probability::zero(), half));
+    }
+
+}
@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::board::board::{Board, Player};
+use crate::game::game::Game;
+use crate::solver::error::SolverError;
+use crate::solver::evaluation::Evaluation;
+
+/// Solves every position reachable from a `Game` position by retrograde
+/// analysis, for games whose positions can recur, where a recursive search
+/// would never finish.
+///
+/// All reachable positions are generated up front, stored as they are rather
+/// than under their transposition keys, since keys may leave out whose turn
+/// it is and results here name the winner.  Finished games are then labelled,
+/// and labels spread backwards through the game graph, nearest to the end of
+/// the game first: a position is won as soon as one move reaches a position
+/// lost for the opponent, and lost once every move reaches a position won for
+/// the opponent.  Positions never labelled can avoid losing forever, so they
+/// are draws.
+pub struct RetrogradeSolver<G: Game = Board> {
+    board: G,
+    positions: Vec<G>,
+    indices: HashMap<G, usize>,
+    evaluations: Vec<Evaluation>,
+}
+
+impl<G: Game> RetrogradeSolver<G> {
+
+    pub fn from_board(board: G) -> Self {
+        let mut solver = RetrogradeSolver { board, positions: Vec::new(), indices: HashMap::new(), evaluations: Vec::new() };
+        solver.solve();
+        solver
+    }
+
+    /// The number of distinct positions reachable from the starting one.
+    pub fn get_n_positions(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn get_evaluation(&self) -> Evaluation {
+        self.get_evaluation_of(&self.board)
+    }
+
+    pub fn get_next_moves(&self) -> Result<Vec<G::Move>, SolverError> {
+        let (next_moves, _) = self.get_next_moves_and_evaluation()?;
+        Ok(next_moves)
+    }
+
+    pub fn get_next_moves_and_evaluation(&self) -> Result<(Vec<G::Move>, Evaluation), SolverError> {
+        self.get_next_moves_and_evaluation_from(&self.board)
+    }
+
+    /// The line follows the same tie-breaking as `AlphaBetaSolver`: among
+    /// equally good moves, the last one is played.  Drawn lines could go on
+    /// forever, so they stop as soon as a position repeats.
+    pub fn get_evaluation_and_line(&self) -> (Evaluation, Vec<G::Move>) {
+        let mut line = Vec::new();
+        let mut board = self.board.clone();
+        let mut seen = HashSet::new();
+        while board.get_active_player().is_some() && seen.insert(board.clone()) {
+            let (next_moves, _) = self.get_next_moves_and_evaluation_from(&board).expect("Only games in progress are continued.");
+            let best_move = *next_moves.last().expect("Games in progress have at least one legal move.");
+            line.push(best_move);
+            board = board.with_legal_move_made(best_move);
+        }
+        (self.get_evaluation(), line)
+    }
+
+    fn get_next_moves_and_evaluation_from(&self, board: &G) -> Result<(Vec<G::Move>, Evaluation), SolverError> {
+        let active_player = match board.get_active_player() {
+            Some(player) => player,
+            None => return Err(SolverError::GameOver),
+        };
+        let mut next_moves = Vec::new();
+        let mut best_evaluation = None;
+        for m in board.get_legal_moves() {
+            let evaluation = self.get_evaluation_of(&board.with_legal_move_made(m)).with_ply_added();
+            match best_evaluation.map(|best| RetrogradeSolver::<G>::compare(evaluation, best, active_player)) {
+                None | Some(Ordering::Greater) => {
+                    best_evaluation = Some(evaluation);
+                    next_moves.clear();
+                    next_moves.push(m);
+                },
+                Some(Ordering::Equal) => next_moves.push(m),
+                Some(Ordering::Less) => (),
+            }
+        }
+        let evaluation = best_evaluation.expect("Games in progress have at least one legal move.");
+        Ok((next_moves, evaluation))
+    }
+
+    fn get_evaluation_of(&self, board: &G) -> Evaluation {
+        let index = self.indices.get(board).expect("Every position reachable from the starting one has been solved.");
+        self.evaluations[*index]
+    }
+
+    /// Orders evaluations by how good they are for `player`.
+    fn compare(evaluation: Evaluation, other: Evaluation, player: Player) -> Ordering {
+        match player {
+            Player::X => evaluation.cmp(&other),
+            Player::O => other.cmp(&evaluation),
+        }
+    }
+
+    fn solve(&mut self) {
+        let mut children: Vec<Vec<usize>> = Vec::new();
+        let mut queue = VecDeque::new();
+        self.add_position(self.board.clone(), &mut queue);
+        while let Some(index) = queue.pop_front() {
+            let board = self.positions[index].clone();
+            let mut board_children = Vec::new();
+            if board.get_active_player().is_some() {
+                for m in board.get_legal_moves() {
+                    let child = board.with_legal_move_made(m);
+                    let child = match self.indices.get(&child) {
+                        Some(child) => *child,
+                        None => self.add_position(child, &mut queue),
+                    };
+                    if !board_children.contains(&child) {
+                        board_children.push(child);
+                    }
+                }
+            }
+            children.push(board_children);
+        }
+
+        let mut parents = vec![Vec::new(); self.positions.len()];
+        for (parent, parent_children) in children.iter().enumerate() {
+            for child in parent_children {
+                parents[*child].push(parent);
+            }
+        }
+        let mut n_unsolved_children = children.iter().map(|children| children.len()).collect::<Vec<usize>>();
+        let mut is_solved = vec![false; self.positions.len()];
+        let mut solved = VecDeque::new();
+        for (index, board) in self.positions.iter().enumerate() {
+            if board.get_active_player().is_none() {
+                self.evaluations[index] = Evaluation::from_outcome(board.get_outcome());
+                is_solved[index] = true;
+                if self.evaluations[index].get_winner().is_some() {
+                    solved.push_back(index);
+                }
+            }
+        }
+
+        // Positions are taken off the queue in order of how many plies they
+        // are from the end of the game, so the first winning move found is the
+        // fastest and the last losing move to be solved is the slowest.
+        while let Some(child) = solved.pop_front() {
+            let evaluation = self.evaluations[child].with_ply_added();
+            let winner = evaluation.get_winner().expect("Only won or lost positions are queued.");
+            for parent in std::mem::take(&mut parents[child]) {
+                if is_solved[parent] {
+                    continue;
+                }
+                let active_player = self.positions[parent].get_active_player().expect("Parents are positions in progress.");
+                n_unsolved_children[parent] -= 1;
+                if winner == active_player || n_unsolved_children[parent] == 0 {
+                    self.evaluations[parent] = evaluation;
+                    is_solved[parent] = true;
+                    solved.push_back(parent);
+                }
+            }
+        }
+    }
+
+    fn add_position(&mut self, board: G, queue: &mut VecDeque<usize>) -> usize {
+        let index = self.positions.len();
+        self.indices.insert(board.clone(), index);
+        self.positions.push(board);
+        self.evaluations.push(Evaluation::draw());
+        queue.push_back(index);
+        index
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_retrograde {
+    use super::*;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+
+    #[test]
+    fn test_retrograde_solver_matches_alpha_beta_solver() {
+        let solver = RetrogradeSolver::from_board(Board::empty());
+        assert_eq!(solver.get_n_positions(), 5478);
+        let board = Board::empty();
+        for first_move in board.get_legal_moves() {
+            let board = board.with_move_made(Player::X, first_move).unwrap();
+            for second_move in board.get_legal_moves() {
+                let board = board.with_move_made(Player::O, second_move).unwrap();
+                let solver = RetrogradeSolver::from_board(board);
+                let mut alpha_beta_solver = AlphaBetaSolver::from_board(board);
+                assert_eq!(solver.get_next_moves_and_evaluation(), alpha_beta_solver.get_next_moves_and_evaluation());
+                assert_eq!(solver.get_evaluation_and_line(), alpha_beta_solver.get_evaluation_and_line());
+            }
+        }
+    }
+
+}
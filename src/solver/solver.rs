@@ -1,39 +1,62 @@
-use crate::board::board::{Board, Move, Outcome, Player};
+use crate::board::board::{Board, Move, Player};
 use crate::tree::tree::{Tree, Node};
+use crate::solver::error::SolverError;
+use crate::solver::evaluation::Evaluation;
+use crate::solver::transposition_table::TranspositionTable;
 
 pub struct Solver {
     tree: Tree,
+    transposition_table: TranspositionTable,
 }
 
 impl Solver {
     pub fn from_board(board: Board) -> Self {
         let tree = Tree::from_board(board);
-        Solver { tree }
+        Solver { tree, transposition_table: TranspositionTable::new() }
     }
 
-    pub fn get_evaluation(&self) -> Evaluation {
-        let root = self.tree.get_root();
-        self.get_evaluation_and_line_for_node(root).0
+    /// Like `from_board`, but positions that are rotations or reflections of
+    /// each other share transposition table entries.  Evaluations and best
+    /// moves are unaffected, though the example line may be a different but
+    /// equally good one.
+    pub fn from_board_with_symmetries(board: Board) -> Self {
+        let tree = Tree::from_board(board);
+        Solver { tree, transposition_table: TranspositionTable::with_symmetries() }
+    }
+
+    pub fn get_evaluation(&mut self) -> Evaluation {
+        self.get_evaluation_and_line().0
+    }
+
+    pub fn get_transposition_table(&self) -> &TranspositionTable {
+        &self.transposition_table
     }
 
-    fn get_evaluation_and_line_for_node(&self, node: &Node) -> (Evaluation, Vec<Move>) {
+    fn get_evaluation_and_line_for_node(transposition_table: &mut TranspositionTable, node: &Node) -> (Evaluation, Vec<Move>) {
+        if let Some(cached) = transposition_table.get(&node.get_board()) {
+            return cached;
+        }
+        let (evaluation, line) = Solver::search_node(transposition_table, node);
+        transposition_table.insert(node.get_board(), evaluation, line.clone());
+        (evaluation, line)
+    }
+
+    fn search_node(transposition_table: &mut TranspositionTable, node: &Node) -> (Evaluation, Vec<Move>) {
         let children = node.get_children().iter();
         if children.len() == 0 {
-            return (Solver::get_evaluation_for_outcome(node.get_board().get_outcome()), Vec::new());
+            return (Evaluation::from_outcome(node.get_board().get_outcome()), Vec::new());
         }
         let active_player = node.get_active_player();
-        let mut best_evaluation = Evaluation(
-            match active_player {
-                Some(Player::X) => -1.,
-                Some(Player::O) => 1.,
-                None => panic!("There's no active player even though there the node has children."),
-            }
-        );
+        let mut best_evaluation = match active_player {
+            Some(player) => Evaluation::win(player.other(), 0),
+            None => panic!("There's no active player even though there the node has children."),
+        };
         
         let mut best_move_line: Vec<Move> = Vec::new();
         for child_move in node.get_legal_moves().iter() {
             let child = node.get_child(child_move.get_row(), child_move.get_column()).expect("Move is legal by definition of get_legal_moves().");
-            let (child_evaluation, child_line) = self.get_evaluation_and_line_for_node(child);
+            let (child_evaluation, child_line) = Solver::get_evaluation_and_line_for_node(transposition_table, child);
+            let child_evaluation = child_evaluation.with_ply_added();
             match active_player {
                 Some(Player::X) => {
                     if child_evaluation >= best_evaluation {
@@ -52,44 +75,29 @@ impl Solver {
                 None => panic!("There's no active player even though there the node has children."),
             }
         }
-        return (best_evaluation, best_move_line);
-    }
-
-    fn get_evaluation_for_outcome(outcome: Outcome) -> Evaluation {
-        let raw_evaluation = match outcome {
-            Outcome::InProgress => 0.,
-            Outcome::Ambiguous => 0.,
-            Outcome::Draw => 0.,
-            Outcome::Victory(Player::X) => 1.,
-            Outcome::Victory(Player::O) => -1.,
-        };
-        return Evaluation(raw_evaluation);
+        (best_evaluation, best_move_line)
     }
 
-    pub fn get_next_moves(&self) -> Result<Vec<Move>, String> {
+    pub fn get_next_moves(&mut self) -> Result<Vec<Move>, SolverError> {
         let (next_moves, _) = self.get_next_moves_and_evaluation()?;
-        return Ok(next_moves);
+        Ok(next_moves)
     }
 
-    pub fn get_next_moves_and_evaluation(&self) -> Result<(Vec<Move>, Evaluation), String> {
+    pub fn get_next_moves_and_evaluation(&mut self) -> Result<(Vec<Move>, Evaluation), SolverError> {
 
         let active_player = match self.tree.get_root().get_active_player() {
             Some(player) => player,
-            None => return Err("The game is already over.".to_string()),
+            None => return Err(SolverError::GameOver),
         };
 
         let root = self.tree.get_root();
         let mut next_moves = Vec::new();
-        let mut best_evaluation = Evaluation(
-            match active_player {
-                Player::X => -2.,
-                Player::O => 2.,
-            }
-        );
+        let mut best_evaluation = Evaluation::win(active_player.other(), 0);
 
         for m in root.get_legal_moves().iter() {
             let child = root.get_child(m.get_row(), m.get_column()).unwrap();
-            let (evaluation, _line) = self.get_evaluation_and_line_for_node(child);
+            let (evaluation, _line) = Solver::get_evaluation_and_line_for_node(&mut self.transposition_table, child);
+            let evaluation = evaluation.with_ply_added();
             if ((evaluation > best_evaluation) && (active_player == Player::X)) ||
                 ((evaluation < best_evaluation) && (active_player == Player::O)) {
                 best_evaluation = evaluation;
@@ -99,43 +107,16 @@ impl Solver {
                 next_moves.push(*m);
             }
         }
-        return Ok((next_moves, best_evaluation));
+        Ok((next_moves, best_evaluation))
     }
 
-    pub fn get_evaluation_and_line(&self) -> (Evaluation, Vec<Move>) {
+    pub fn get_evaluation_and_line(&mut self) -> (Evaluation, Vec<Move>) {
         let root = self.tree.get_root();
-        self.get_evaluation_and_line_for_node(root)
+        Solver::get_evaluation_and_line_for_node(&mut self.transposition_table, root)
     }
 
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
-pub struct Evaluation(f32);
-
-impl Evaluation {
-
-    pub fn new(evaluation: f32) -> Self {
-        Evaluation(evaluation)
-    }
-
-    pub fn get_evaluation(&self) -> f32 {
-        self.0
-    }
-
-    pub fn to_string(&self) -> String {
-        if self.0 > 0.9999 {
-            return "X is Winning".to_string();
-        } else if self.0 < -0.9999 {
-            return "O is Winning".to_string();
-        } else if self.0.abs() < 0.0001 {
-            return "Drawn".to_string();
-        } else {
-            return "Ambiguous".to_string();
-        }
-    }
-}
-
-
 #[cfg(test)]
 mod test_solver {
     use super::*;
@@ -150,7 +131,7 @@ mod test_solver {
                     XOX",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(1.)
+            Evaluation::win(Player::X, 0)
         );
         
         assert_eq!(
@@ -161,7 +142,7 @@ mod test_solver {
                     XOX",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(1.)
+            Evaluation::win(Player::X, 1)
         );
 
         assert_eq!(
@@ -172,7 +153,7 @@ mod test_solver {
                     XXO",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(0.)
+            Evaluation::draw()
         );
 
         assert_eq!(
@@ -183,7 +164,7 @@ mod test_solver {
                     XXO",
                 ).unwrap()
             ).get_evaluation(),
-            Evaluation(-1.)
+            Evaluation::win(Player::O, 1)
         );
 
         
@@ -194,7 +175,7 @@ mod test_solver {
                     O__
                     XXO",
                 ).unwrap()
-            ).get_evaluation() < Evaluation(0.0001)
+            ).get_evaluation() <= Evaluation::draw()
         );
     }
 
@@ -207,10 +188,10 @@ mod test_solver {
             O__"
         ).unwrap();
         assert_eq!(board.get_active_player(), Some(Player::X));
-        let solver = Solver::from_board(board);
+        let mut solver = Solver::from_board(board);
 
         let (evaluation, line) = solver.get_evaluation_and_line();
-        assert_eq!(evaluation, Evaluation(-1.));
+        assert_eq!(evaluation, Evaluation::win(Player::O, 2));
         assert_eq!(line, vec![Move { row: 2, col: 2 }, Move { row: 2, col: 1} ]);
 
         let board = Board::from_position(
@@ -219,14 +200,14 @@ mod test_solver {
             ___"
         ).unwrap();
         assert_eq!(board.get_active_player(), Some(Player::O));
-        let solver = Solver::from_board(board);
+        let mut solver = Solver::from_board(board);
 
         let (evaluation, line) = solver.get_evaluation_and_line();
-        assert_eq!(evaluation, Evaluation(-1.));
+        assert_eq!(evaluation, Evaluation::win(Player::O, 1));
         assert_eq!(line, vec![Move { row: 2, col: 1 }]);
 
         let (best_moves, _) = solver.get_next_moves_and_evaluation().unwrap();
-        assert_eq!(best_moves, vec![Move { row: 2, col: 0 }, Move { row: 2, col: 1 }]);
+        assert_eq!(best_moves, vec![Move { row: 2, col: 1 }]);
 
         let board = Board::from_position(
             "XOO
@@ -234,14 +215,47 @@ mod test_solver {
             ___"
         ).unwrap();
         assert_eq!(board.get_active_player(), Some(Player::X));
-        let solver = Solver::from_board(board);
+        let mut solver = Solver::from_board(board);
         
         let (evaluation, line) = solver.get_evaluation_and_line();
-        assert_eq!(evaluation, Evaluation(1.));
+        assert_eq!(evaluation, Evaluation::win(Player::X, 1));
         assert_eq!(line, vec![Move::new(2, 2)]);
 
         let (best_moves, _) = solver.get_next_moves_and_evaluation().unwrap();
-        assert_eq!(best_moves, vec![Move { row: 1, col: 0 }, Move { row: 1, col: 2 }, Move { row: 2, col: 0 }, Move { row: 2, col: 2 }]);
+        assert_eq!(best_moves, vec![Move { row: 2, col: 2 }]);
+
+    }
 
+    #[test]
+    fn test_solver_reuses_transposition_table() {
+        let mut solver = Solver::from_board(Board::empty());
+        assert_eq!(solver.get_evaluation(), Evaluation::draw());
+
+        let transposition_table = solver.get_transposition_table();
+        assert_eq!(transposition_table.len(), 5478);
+        assert_eq!(transposition_table.get_misses(), 5478);
+        assert!(transposition_table.get_hits() > 0);
+
+        let hits = transposition_table.get_hits();
+        assert_eq!(solver.get_evaluation(), Evaluation::draw());
+        assert_eq!(solver.get_transposition_table().get_hits(), hits + 1);
+        assert_eq!(solver.get_transposition_table().get_misses(), 5478);
+    }
+
+    #[test]
+    fn test_solver_with_symmetries_shares_symmetric_positions() {
+        let mut solver = Solver::from_board_with_symmetries(Board::empty());
+        assert_eq!(solver.get_evaluation(), Evaluation::draw());
+        assert_eq!(solver.get_transposition_table().len(), 765);
+
+        let board = Board::from_position(
+            "XO_
+            ___
+            ___",
+        ).unwrap();
+        assert_eq!(
+            Solver::from_board_with_symmetries(board).get_next_moves_and_evaluation(),
+            Solver::from_board(board).get_next_moves_and_evaluation()
+        );
     }
 }
\ No newline at end of file
@@ -0,0 +1,299 @@
+use std::fs;
+
+use crate::board::board::{Bitboard, Board, Move, Outcome, Player};
+use crate::board::error::{IndexError, ParseError};
+use crate::board::index::N_LEGAL_INDICES;
+use crate::board::shape::BoardShape;
+use crate::solver::error::{SolverError, TablebaseError};
+use crate::solver::evaluation::Evaluation;
+
+const MAGIC: &[u8; 4] = b"TTTB";
+const VERSION: u8 = 3;
+const HEADER_LENGTH: usize = 9;
+const ENTRY_LENGTH: usize = 3;
+
+/// The solved value of a single position: its evaluation and the set of
+/// squares the side to move can play to achieve it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TablebaseEntry {
+    evaluation: Evaluation,
+    best_moves: Bitboard,
+}
+
+impl TablebaseEntry {
+
+    pub fn get_evaluation(&self) -> Evaluation {
+        self.evaluation
+    }
+
+    pub fn get_best_moves(&self) -> Vec<Move> {
+        let shape = BoardShape::standard();
+        self.best_moves.get_squares().into_iter().map(|square| shape.get_move(square)).collect()
+    }
+
+}
+
+/// Every position reachable from the empty standard board, solved once and
+/// answered by lookup.
+///
+/// On disk, a tablebase is the magic bytes `TTTB`, a version byte and the
+/// number of entries as a little-endian `u32`, followed by one 3-byte entry per
+/// position in order of `Board::to_legal_index()`: a byte holding the value in
+/// its low two bits (0 draw, 1 X wins, 2 O wins) and the plies to the result
+/// above them, then the best-move mask as a little-endian `u16` with bit
+/// `row * 3 + col` set for each best move.
+pub struct Tablebase {
+    entries: Vec<TablebaseEntry>,
+}
+
+impl Tablebase {
+
+    pub fn generate() -> Self {
+        let mut entries = vec![None; N_LEGAL_INDICES];
+        Tablebase::solve(Board::empty(), &mut entries);
+        Tablebase {
+            entries: entries.into_iter()
+                .map(|entry| entry.expect("Every legal position is reachable from the empty board."))
+                .collect()
+        }
+    }
+
+    fn solve(board: Board, entries: &mut Vec<Option<TablebaseEntry>>) -> Evaluation {
+        let legal_index = board.to_legal_index().expect("Positions reached from the empty board are legal.");
+        if let Some(entry) = entries[legal_index] {
+            return entry.evaluation;
+        }
+        let entry = match (board.get_active_player(), board.get_outcome()) {
+            (None, Outcome::Victory(player)) => TablebaseEntry { evaluation: Evaluation::win(player, 0), best_moves: Bitboard::empty() },
+            (None, _) => TablebaseEntry { evaluation: Evaluation::draw(), best_moves: Bitboard::empty() },
+            (Some(player), _) => {
+                let mut best_evaluation = Evaluation::win(player.other(), 0);
+                let mut best_moves = Bitboard::empty();
+                for m in board.get_legal_moves() {
+                    let child = board.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().");
+                    let evaluation = Tablebase::solve(child, entries).with_ply_added();
+                    let is_better = match player {
+                        Player::X => evaluation > best_evaluation,
+                        Player::O => evaluation < best_evaluation,
+                    };
+                    if is_better {
+                        best_evaluation = evaluation;
+                        best_moves = Bitboard::empty();
+                    }
+                    if evaluation == best_evaluation {
+                        best_moves.set_square(board.get_shape().get_square(m.get_row(), m.get_column()));
+                    }
+                }
+                TablebaseEntry { evaluation: best_evaluation, best_moves }
+            },
+        };
+        entries[legal_index] = Some(entry);
+        entry.evaluation
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get_entry(&self, board: &Board) -> Result<TablebaseEntry, SolverError> {
+        if !board.get_shape().is_standard() {
+            return Err(SolverError::UnsupportedShape(board.get_shape()));
+        }
+        if !board.get_rules().is_standard() {
+            return Err(SolverError::UnsupportedRules(board.get_rules()));
+        }
+        let legal_index = board.to_legal_index().map_err(|error| match error {
+            IndexError::IllegalPosition(illegality) => SolverError::IllegalPosition(illegality),
+            IndexError::BlockedSquares => SolverError::UnsupportedBlockedSquares,
+            IndexError::OutOfRange { .. } | IndexError::TooManySquares(_) | IndexError::UnsupportedShape(_) => SolverError::UnsupportedShape(board.get_shape()),
+        })?;
+        Ok(self.entries[legal_index])
+    }
+
+    pub fn get_evaluation(&self, board: &Board) -> Result<Evaluation, SolverError> {
+        Ok(self.get_entry(board)?.get_evaluation())
+    }
+
+    pub fn get_next_moves(&self, board: &Board) -> Result<Vec<Move>, SolverError> {
+        let (next_moves, _) = self.get_next_moves_and_evaluation(board)?;
+        Ok(next_moves)
+    }
+
+    pub fn get_next_moves_and_evaluation(&self, board: &Board) -> Result<(Vec<Move>, Evaluation), SolverError> {
+        let entry = self.get_entry(board)?;
+        if board.get_active_player().is_none() {
+            return Err(SolverError::GameOver);
+        }
+        Ok((entry.get_best_moves(), entry.get_evaluation()))
+    }
+
+    /// The line follows the same tie-breaking as `Solver`: among equally good
+    /// moves, the last one is played.
+    pub fn get_evaluation_and_line(&self, board: &Board) -> Result<(Evaluation, Vec<Move>), SolverError> {
+        let evaluation = self.get_evaluation(board)?;
+        let mut line = Vec::new();
+        let mut current_board = *board;
+        while let Some(player) = current_board.get_active_player() {
+            let best_move = *self.get_entry(&current_board)?.get_best_moves().last().expect("Positions in progress have a best move.");
+            line.push(best_move);
+            current_board = current_board.with_move_made(player, best_move)?;
+        }
+        Ok((evaluation, line))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LENGTH + ENTRY_LENGTH * self.entries.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for entry in self.entries.iter() {
+            let (value, n_plies) = match entry.evaluation {
+                Evaluation::Draw => (0, 0),
+                Evaluation::Win { player: Player::X, n_plies } => (1, n_plies),
+                Evaluation::Win { player: Player::O, n_plies } => (2, n_plies),
+                Evaluation::Unknown(_) => panic!("Tablebase entries are always solved."),
+            };
+            bytes.push(value | ((n_plies as u8) << 2));
+            bytes.extend_from_slice(&(entry.best_moves.to_bits() as u16).to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TablebaseError> {
+        if bytes.len() < HEADER_LENGTH || &bytes[0..4] != MAGIC {
+            return Err(TablebaseError::NotATablebase);
+        }
+        if bytes[4] != VERSION {
+            return Err(TablebaseError::UnsupportedVersion { version: bytes[4], expected: VERSION });
+        }
+        let n_entries = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+        if n_entries != N_LEGAL_INDICES {
+            return Err(TablebaseError::WrongEntryCount { n_entries, expected: N_LEGAL_INDICES });
+        }
+        if bytes.len() != HEADER_LENGTH + ENTRY_LENGTH * n_entries {
+            return Err(TablebaseError::WrongLength { length: bytes.len(), expected: HEADER_LENGTH + ENTRY_LENGTH * n_entries });
+        }
+
+        let mut entries = Vec::with_capacity(n_entries);
+        for (legal_index, chunk) in bytes[HEADER_LENGTH..].chunks(ENTRY_LENGTH).enumerate() {
+            let n_plies = (chunk[0] >> 2) as usize;
+            let evaluation = match chunk[0] & 0b11 {
+                0 => Evaluation::draw(),
+                1 => Evaluation::win(Player::X, n_plies),
+                2 => Evaluation::win(Player::O, n_plies),
+                _ => return Err(TablebaseError::InvalidEntry(legal_index)),
+            };
+            let best_moves = Bitboard::from_bits(u16::from_le_bytes([chunk[1], chunk[2]]) as u64);
+            if !BoardShape::standard().get_full_bitboard().contains(best_moves) {
+                return Err(TablebaseError::InvalidBestMoves(ParseError::BitsOutsideBoard(best_moves.to_bits())));
+            }
+            entries.push(TablebaseEntry { evaluation, best_moves });
+        }
+        Ok(Tablebase { entries })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), TablebaseError> {
+        fs::write(path, self.to_bytes()).map_err(|error| TablebaseError::Io { path: path.to_string(), kind: error.kind() })
+    }
+
+    pub fn load(path: &str) -> Result<Self, TablebaseError> {
+        let bytes = fs::read(path).map_err(|error| TablebaseError::Io { path: path.to_string(), kind: error.kind() })?;
+        Tablebase::from_bytes(&bytes)
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_tablebase {
+    use super::*;
+    use crate::board::rules::Rules;
+    use crate::board::validation::Illegality;
+    use crate::solver::solver::Solver;
+
+    #[test]
+    fn test_tablebase_covers_every_reachable_position() {
+        let tablebase = Tablebase::generate();
+        assert_eq!(tablebase.len(), 5478);
+        assert_eq!(tablebase.get_evaluation(&Board::empty()), Ok(Evaluation::draw()));
+        assert_eq!(
+            tablebase.get_evaluation(
+                &Board::from_position(
+                    "XXX
+                    ___
+                    OOO",
+                ).unwrap()
+            ),
+            Err(SolverError::IllegalPosition(Illegality::BothPlayersWon))
+        );
+        let board = Board::from_position("____/____/____/____").unwrap();
+        assert_eq!(tablebase.get_evaluation(&board), Err(SolverError::UnsupportedShape(board.get_shape())));
+        let board = Board::empty().with_rules(Rules::misere());
+        assert_eq!(tablebase.get_evaluation(&board), Err(SolverError::UnsupportedRules(Rules::misere())));
+        let board = Board::from_position("X#_ _O_ ___").unwrap();
+        assert_eq!(tablebase.get_evaluation(&board), Err(SolverError::UnsupportedBlockedSquares));
+    }
+
+    #[test]
+    fn test_tablebase_matches_solver() {
+        let tablebase = Tablebase::generate();
+        let boards = vec![
+            Board::empty(),
+            Board::from_position(
+                "XO_
+                ___
+                ___",
+            ).unwrap(),
+            Board::from_position(
+                "XO_
+                _X_
+                __O",
+            ).unwrap(),
+            Board::from_position(
+                "XO_
+                XOX
+                O__",
+            ).unwrap(),
+            Board::from_position(
+                "XOX
+                OXO
+                XOX",
+            ).unwrap(),
+        ];
+        for board in boards {
+            let mut solver = Solver::from_board(board);
+            assert_eq!(tablebase.get_evaluation(&board), Ok(solver.get_evaluation()));
+            assert_eq!(tablebase.get_evaluation_and_line(&board), Ok(solver.get_evaluation_and_line()));
+            assert_eq!(tablebase.get_next_moves_and_evaluation(&board), solver.get_next_moves_and_evaluation());
+        }
+    }
+
+    #[test]
+    fn test_tablebase_round_trips_through_bytes() {
+        let tablebase = Tablebase::generate();
+        let bytes = tablebase.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LENGTH + ENTRY_LENGTH * 5478);
+        assert_eq!(&bytes[0..5], b"TTTB\x03");
+
+        let loaded = Tablebase::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.entries, tablebase.entries);
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = 1;
+        assert_eq!(Tablebase::from_bytes(&wrong_version).err(), Some(TablebaseError::UnsupportedVersion { version: 1, expected: 3 }));
+        assert_eq!(
+            Tablebase::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(TablebaseError::WrongLength { length: bytes.len() - 1, expected: bytes.len() })
+        );
+        assert_eq!(Tablebase::from_bytes(b"not a tablebase").err(), Some(TablebaseError::NotATablebase));
+        assert_eq!(
+            Tablebase::load("/nonexistent/tablebase").err(),
+            Some(TablebaseError::Io { path: "/nonexistent/tablebase".to_string(), kind: std::io::ErrorKind::NotFound })
+        );
+    }
+
+}
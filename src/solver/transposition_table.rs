@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use crate::board::board::{Board, Move};
+use crate::board::symmetry::Symmetry;
+use crate::solver::evaluation::Evaluation;
+
+/// Caches evaluations and best lines by position.  When symmetries are used,
+/// entries are stored under the canonical form of the board, with the line
+/// in the canonical orientation, so every orientation of a position shares
+/// one entry.
+pub struct TranspositionTable {
+    entries: HashMap<Board, (Evaluation, Vec<Move>)>,
+    use_symmetries: bool,
+    hits: usize,
+    misses: usize,
+}
+
+impl TranspositionTable {
+
+    pub fn new() -> Self {
+        TranspositionTable {
+            entries: HashMap::new(),
+            use_symmetries: false,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn with_symmetries() -> Self {
+        TranspositionTable {
+            use_symmetries: true,
+            ..TranspositionTable::new()
+        }
+    }
+
+    pub fn uses_symmetries(&self) -> bool {
+        self.use_symmetries
+    }
+
+    fn get_key(&self, board: &Board) -> (Board, Symmetry) {
+        match self.use_symmetries {
+            true => board.get_canonical_form(),
+            false => (*board, Symmetry::Identity),
+        }
+    }
+
+    pub fn get(&mut self, board: &Board) -> Option<(Evaluation, Vec<Move>)> {
+        let (key, symmetry) = self.get_key(board);
+        match self.entries.get(&key) {
+            Some((evaluation, line)) => {
+                self.hits += 1;
+                let inverse = symmetry.inverse();
+                Some((*evaluation, line.iter().map(|m| m.transformed(inverse, &board.get_shape())).collect()))
+            },
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, board: Board, evaluation: Evaluation, line: Vec<Move>) {
+        let (key, symmetry) = self.get_key(&board);
+        let line = line.iter().map(|m| m.transformed(symmetry, &board.get_shape())).collect();
+        self.entries.insert(key, (evaluation, line));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get_hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn get_misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        TranspositionTable::new()
+    }
+}
+
+
+#[cfg(test)]
+mod test_transposition_table {
+    use super::*;
+    use crate::board::board::Player;
+
+    #[test]
+    fn test_transposition_table_counts_hits_and_misses() {
+        let mut table = TranspositionTable::new();
+        let board = Board::from_position(
+            "XO_
+            ___
+            ___",
+        ).unwrap();
+
+        assert_eq!(table.get(&board), None);
+        table.insert(board, Evaluation::win(Player::X, 0), vec![Move::new(1, 1)]);
+        assert_eq!(table.get(&board), Some((Evaluation::win(Player::X, 0), vec![Move::new(1, 1)])));
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.get_hits(), 1);
+        assert_eq!(table.get_misses(), 1);
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.get_hits(), 0);
+    }
+
+    #[test]
+    fn test_transposition_table_shares_entries_between_symmetric_boards() {
+        let mut table = TranspositionTable::with_symmetries();
+        let board = Board::from_position(
+            "XO_
+            ___
+            ___",
+        ).unwrap();
+        table.insert(board, Evaluation::win(Player::X, 5), vec![Move::new(1, 0), Move::new(2, 0)]);
+
+        let rotated_board = Board::from_position(
+            "__X
+            __O
+            ___",
+        ).unwrap();
+        assert_eq!(
+            table.get(&rotated_board),
+            Some((Evaluation::win(Player::X, 5), vec![Move::new(0, 1), Move::new(0, 0)]))
+        );
+        assert_eq!(table.len(), 1);
+    }
+
+}
@@ -1,4 +1,5 @@
 use crate::board::board::{Board, Move, Outcome, Player};
+use crate::board::error::MoveError;
 
 pub struct Tree {
     root: Node,
@@ -32,15 +33,9 @@ impl Node {
         let children = match board.get_outcome() {
             Outcome::InProgress => {
                 let player = board.get_active_player().expect("We've already checked that the game isn't already over.");
-                let mut children = Vec::new();
-                for row in 0..3 {
-                    for col in 0..3 {
-                        if let Ok(child_board) = board.with_move_made(player, Move::new(row, col)) {
-                            children.push(Node::from_board(child_board));
-                        }
-                    }
-                }
-                children
+                board.get_legal_moves().into_iter()
+                    .map(|m| Node::from_board(board.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")))
+                    .collect()
             },
             _ => Vec::new(),
         };
@@ -65,9 +60,9 @@ impl Node {
 
     pub fn get_max_depth(&self) -> usize {
         if self.n_children() == 0 {
-            return 1;
+            1
         } else {
-            return 1 + self.children.iter().map(|child| child.get_max_depth()).max().unwrap();
+            1 + self.children.iter().map(|child| child.get_max_depth()).max().unwrap()
         }
     }
 
@@ -75,17 +70,17 @@ impl Node {
         &self.children
     }
 
-    pub fn get_child(&self, row: usize, col: usize) -> Result<&Node, String> {
+    pub fn get_child(&self, row: usize, col: usize) -> Result<&Node, MoveError> {
         if self.board.get_outcome() != Outcome::InProgress {
-            return Err("Game is already over".to_string());
+            return Err(MoveError::GameOver);
         }
         let player = self.board.get_active_player().expect("We've already checked that the game isn't already over.");
-        self.children.iter()
-            .find(|child| Ok(child.board) == self.board.with_move_made(player, Move::new(row, col)))
-            .map_or(
-                Err(format!("There is no child with the move {}", Move::new(row, col).to_string())),
-                |x| Ok(x)
-            )
+        let child_board = self.board.with_move_made(player, Move::new(row, col))?;
+        Ok(
+            self.children.iter()
+                .find(|child| child.board == child_board)
+                .expect("Every legal move has a child.")
+        )
     }
 
     pub fn get_outcome(&self) -> Outcome {
@@ -0,0 +1,368 @@
+use crate::board::board::{Bitboard, Board, Move, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::board::shape::BoardShape;
+use crate::game::game::Game;
+
+const N_BOARDS: usize = 9;
+const SIZE: usize = 3;
+
+/// Heuristic weights for a line holding one or two of a player's marks (or
+/// won boards) and none of the opponent's.
+const LOCAL_LINE_SCORES: [i32; 3] = [0, 1, 4];
+const META_LINE_SCORES: [i32; 3] = [0, 20, 80];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UltimateMove {
+    board: usize,
+    square: Move,
+}
+
+impl UltimateMove {
+
+    /// Local boards are numbered 0 to 8 in reading order.
+    pub fn new(board: usize, square: Move) -> Self {
+        UltimateMove { board, square }
+    }
+
+    pub fn get_board(&self) -> usize {
+        self.board
+    }
+
+    pub fn get_square(&self) -> Move {
+        self.square
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("Board {} {}", self.board, self.square.to_string())
+    }
+
+}
+
+/// Ultimate tic-tac-toe: nine local boards arranged as a 3x3 meta board.
+/// Winning a local board claims its square on the meta board, and the game
+/// is won with three local boards in a row.  Each move sends the opponent to
+/// the local board matching the square just played, unless that board is
+/// finished, in which case they may play on any unfinished board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UltimateBoard {
+    boards: [Board; N_BOARDS],
+    meta_board: Board,
+    next_board: Option<usize>,
+}
+
+impl UltimateBoard {
+
+    pub fn empty() -> Self {
+        UltimateBoard {
+            boards: [Board::empty(); N_BOARDS],
+            meta_board: Board::empty(),
+            next_board: None,
+        }
+    }
+
+    /// Parses the full 9x9 grid, with rows separated by `/` or whitespace.
+    /// The `|` and `---+---+---` separators printed by `to_string()` are
+    /// ignored, so printed positions parse back.  The player to move may
+    /// play on any board; see `with_next_board()`.
+    pub fn from_position(position: &str) -> Result<Self, ParseError> {
+        let rows = position.split(|c: char| c == '/' || c.is_whitespace())
+            .map(|row| row.chars().filter(|c| *c != '|').collect::<Vec<char>>())
+            .filter(|row| !row.is_empty() && !row.iter().all(|c| *c == '-' || *c == '+'))
+            .collect::<Vec<Vec<char>>>();
+        let n_rows = N_BOARDS;
+        let rows = match rows.len() {
+            1 if rows[0].len() == n_rows * n_rows => rows[0].chunks(n_rows).map(|row| row.to_vec()).collect(),
+            length if length == n_rows => rows,
+            _ => return Err(ParseError::InvalidPositionLength { position: position.to_string(), length: rows.concat().len() }),
+        };
+
+        let mut ultimate_board = UltimateBoard::empty();
+        for (row, squares) in rows.iter().enumerate() {
+            if squares.len() != n_rows {
+                return Err(ParseError::InconsistentRowLength { row, length: squares.len(), expected: n_rows });
+            }
+            for (col, c) in squares.iter().enumerate() {
+                let board = (row / SIZE) * SIZE + col / SIZE;
+                let square = Move::new(row % SIZE, col % SIZE);
+                let player = match c {
+                    'X' => Player::X,
+                    'O' => Player::O,
+                    '_' => continue,
+                    _ => return Err(ParseError::InvalidCharacter(*c)),
+                };
+                ultimate_board.boards[board].place_mark(player, square).expect("Each square is read once.");
+            }
+        }
+        for board in 0..N_BOARDS {
+            ultimate_board.update_meta_board(board);
+        }
+        Ok(ultimate_board)
+    }
+
+    /// The same position with the player to move sent to `next_board`, or
+    /// free to play anywhere when it's `None`.
+    pub fn with_next_board(&self, next_board: Option<usize>) -> Self {
+        UltimateBoard { next_board, ..*self }
+    }
+
+    pub fn get_boards(&self) -> &[Board; N_BOARDS] {
+        &self.boards
+    }
+
+    pub fn get_meta_board(&self) -> Board {
+        self.meta_board
+    }
+
+    pub fn get_next_board(&self) -> Option<usize> {
+        self.next_board
+    }
+
+    pub fn is_finished(&self, board: usize) -> bool {
+        self.boards[board].get_outcome() != Outcome::InProgress
+    }
+
+    /// The boards the player to move may play on.
+    pub fn get_playable_boards(&self) -> Vec<usize> {
+        match self.next_board {
+            Some(board) if !self.is_finished(board) => vec![board],
+            _ => (0..N_BOARDS).filter(|board| !self.is_finished(*board)).collect(),
+        }
+    }
+
+    pub fn get_outcome(&self) -> Outcome {
+        match self.meta_board.get_outcome() {
+            Outcome::Victory(player) => Outcome::Victory(player),
+            _ if (0..N_BOARDS).all(|board| self.is_finished(board)) => Outcome::Draw,
+            _ => Outcome::InProgress,
+        }
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        if self.get_outcome() != Outcome::InProgress {
+            return None;
+        }
+        let n_x = self.boards.iter().map(|board| board.get_x_bitboard().n_set()).sum::<usize>();
+        let n_o = self.boards.iter().map(|board| board.get_o_bitboard().n_set()).sum::<usize>();
+        match n_x == n_o {
+            true => Some(Player::X),
+            false => Some(Player::O),
+        }
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<UltimateMove> {
+        if self.get_active_player().is_none() {
+            return Vec::new();
+        }
+        let mut moves = Vec::new();
+        for board in self.get_playable_boards() {
+            for square in self.boards[board].get_legal_moves() {
+                moves.push(UltimateMove::new(board, square));
+            }
+        }
+        moves
+    }
+
+    pub fn make_move(&mut self, m: UltimateMove) -> Result<(), MoveError> {
+        let player = match self.get_active_player() {
+            Some(player) => player,
+            None => return Err(MoveError::GameOver),
+        };
+        if m.board >= N_BOARDS {
+            return Err(MoveError::NoSuchBoard(m.board));
+        }
+        if self.is_finished(m.board) {
+            return Err(MoveError::BoardFinished(m.board));
+        }
+        if let Some(expected) = self.next_board {
+            if expected != m.board && !self.is_finished(expected) {
+                return Err(MoveError::WrongBoard { board: m.board, expected });
+            }
+        }
+        self.boards[m.board].place_mark(player, m.square)?;
+        self.update_meta_board(m.board);
+        self.next_board = Some(m.square.get_row() * SIZE + m.square.get_column());
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, m: UltimateMove) -> Result<Self, MoveError> {
+        let mut new_board = *self;
+        new_board.make_move(m)?;
+        Ok(new_board)
+    }
+
+    fn update_meta_board(&mut self, board: usize) {
+        if let Outcome::Victory(player) = self.boards[board].get_outcome() {
+            self.meta_board.place_mark(player, Move::new(board / SIZE, board % SIZE)).expect("A board is only won once.");
+        }
+    }
+
+    /// Prints the 9x9 grid with the local boards separated by `|` and
+    /// `---+---+---`.
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        let mut rows = Vec::new();
+        for row in 0..N_BOARDS {
+            if row > 0 && row % SIZE == 0 {
+                rows.push("---+---+---".to_string());
+            }
+            rows.push(
+                (0..SIZE)
+                    .map(|col| {
+                        let board = self.boards[(row / SIZE) * SIZE + col].to_string();
+                        board.lines().nth(row % SIZE).expect("Local boards have three rows.").to_string()
+                    })
+                    .collect::<Vec<String>>()
+                    .join("|")
+            );
+        }
+        rows.join("\n")
+    }
+
+    /// Scores open lines, from X's perspective: lines on the meta board that
+    /// a player has won one or two boards of and the opponent none, and
+    /// likewise lines on each unfinished local board.
+    pub fn get_heuristic(&self) -> i32 {
+        let win_masks = BoardShape::standard().get_win_masks();
+        let mut drawn_boards = Bitboard::empty();
+        for board in 0..N_BOARDS {
+            if self.boards[board].get_outcome() == Outcome::Draw {
+                drawn_boards.set_square(board);
+            }
+        }
+
+        let mut score = 0;
+        let meta_x = self.meta_board.get_x_bitboard().union(&drawn_boards);
+        let meta_o = self.meta_board.get_o_bitboard().union(&drawn_boards);
+        for mask in win_masks {
+            score += META_LINE_SCORES[self.meta_board.get_x_bitboard().intersection(mask).n_set().min(2)] * (meta_o.intersection(mask).is_empty() as i32);
+            score -= META_LINE_SCORES[self.meta_board.get_o_bitboard().intersection(mask).n_set().min(2)] * (meta_x.intersection(mask).is_empty() as i32);
+        }
+        for board in self.boards.iter().filter(|board| board.get_outcome() == Outcome::InProgress) {
+            for mask in win_masks {
+                let n_x = board.get_x_bitboard().intersection(mask).n_set();
+                let n_o = board.get_o_bitboard().intersection(mask).n_set();
+                if n_o == 0 {
+                    score += LOCAL_LINE_SCORES[n_x.min(2)];
+                }
+                if n_x == 0 {
+                    score -= LOCAL_LINE_SCORES[n_o.min(2)];
+                }
+            }
+        }
+        score
+    }
+
+}
+
+impl Game for UltimateBoard {
+    type Move = UltimateMove;
+
+    fn get_outcome(&self) -> Outcome {
+        UltimateBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        UltimateBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<UltimateMove> {
+        UltimateBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: UltimateMove) -> Self {
+        self.with_move_made(m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    fn get_heuristic(&self) -> i32 {
+        UltimateBoard::get_heuristic(self)
+    }
+}
+
+
+#[cfg(test)]
+mod test_ultimate {
+    use super::*;
+    use crate::solver::depth_limited::DepthLimitedSolver;
+    use crate::solver::evaluation::Evaluation;
+
+    #[test]
+    fn test_moves_send_the_opponent_to_a_board() {
+        let board = UltimateBoard::empty();
+        assert_eq!(board.get_legal_moves().len(), 81);
+
+        let board = board.with_move_made(UltimateMove::new(4, Move::new(0, 2))).unwrap();
+        assert_eq!(board.get_next_board(), Some(2));
+        assert_eq!(board.get_legal_moves().len(), 9);
+        assert!(board.get_legal_moves().iter().all(|m| m.get_board() == 2));
+        assert_eq!(
+            board.with_move_made(UltimateMove::new(4, Move::new(0, 0))),
+            Err(MoveError::WrongBoard { board: 4, expected: 2 })
+        );
+    }
+
+    #[test]
+    fn test_finished_boards_free_the_opponent() {
+        let board = UltimateBoard::from_position(
+            "XXX|___|___
+            ___|___|___
+            ___|___|___
+            ---+---+---
+            OO_|___|___
+            ___|___|___
+            ___|___|___
+            ---+---+---
+            ___|___|___
+            ___|___|___
+            ___|___|___",
+        ).unwrap().with_next_board(Some(0));
+        assert_eq!(board.get_meta_board(), Board::from_position("X__ ___ ___").unwrap());
+        assert_eq!(board.get_active_player(), Some(Player::O));
+        assert_eq!(board.get_playable_boards(), (1..9).collect::<Vec<usize>>());
+        assert_eq!(board.get_legal_moves().len(), 70);
+        assert_eq!(UltimateBoard::from_position(&board.to_string()), Ok(board.with_next_board(None)));
+    }
+
+    #[test]
+    fn test_ultimate_board_is_won_on_the_meta_board() {
+        let board = UltimateBoard::from_position(
+            "XXX|XXX|XX_
+            OO_|OO_|OO_
+            ___|___|___
+            ---+---+---
+            O__|___|___
+            ___|___|O__
+            ___|___|___
+            ---+---+---
+            ___|___|___
+            ___|___|___
+            ___|___|___",
+        ).unwrap();
+        assert_eq!(board.get_outcome(), Outcome::InProgress);
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert!(board.get_heuristic() > 0);
+
+        let won = board.with_move_made(UltimateMove::new(2, Move::new(0, 2))).unwrap();
+        assert_eq!(won.get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(won.get_legal_moves(), vec![]);
+
+        let mut solver = DepthLimitedSolver::from_board(board, 2);
+        assert_eq!(
+            solver.get_next_moves_and_evaluation(),
+            Ok((vec![UltimateMove::new(2, Move::new(0, 2))], Evaluation::win(Player::X, 1)))
+        );
+    }
+
+    #[test]
+    fn test_depth_limited_search_plays_the_opening() {
+        let mut solver = DepthLimitedSolver::from_board(UltimateBoard::empty(), 4);
+        let (evaluation, line) = solver.get_evaluation_and_line();
+        assert!(!evaluation.is_solved());
+        assert_eq!(line.len(), 4);
+        let mut board = UltimateBoard::empty();
+        for m in line {
+            board.make_move(m).unwrap();
+        }
+    }
+
+}
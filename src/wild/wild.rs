@@ -0,0 +1,219 @@
+use crate::board::board::{Board, Move, Outcome, Player};
+use crate::board::error::{MoveError, ParseError};
+use crate::game::game::Game;
+
+/// A move in Wild tic-tac-toe: a square, and which symbol to put there.
+/// Symbols are written as `Player`s, but either player may place either one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WildMove {
+    square: Move,
+    symbol: Player,
+}
+
+impl WildMove {
+
+    pub fn new(square: Move, symbol: Player) -> Self {
+        WildMove { square, symbol }
+    }
+
+    pub fn get_square(&self) -> Move {
+        self.square
+    }
+
+    pub fn get_symbol(&self) -> Player {
+        self.symbol
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("{} at {}", self.symbol.to_string(), self.square.to_string())
+    }
+
+    /// Parses the symbol followed by the square, like `"O 1 2"`, `"X,0,2"` or
+    /// `"X12"`.
+    pub fn from_string(s: &str) -> Result<WildMove, ParseError> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        let s = s.trim();
+        let symbol = match s.chars().next() {
+            Some('X') => Player::X,
+            Some('O') => Player::O,
+            _ => return Err(invalid()),
+        };
+        let square = s[1..].trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let square = Move::from_string(square).map_err(|_| invalid())?;
+        Ok(WildMove::new(square, symbol))
+    }
+
+}
+
+/// Wild tic-tac-toe: on each turn a player puts either symbol on an empty
+/// square, and whoever completes a line of either symbol wins (or, under
+/// misère rules, loses).
+///
+/// Since marks no longer say who placed them, the player to move is kept
+/// explicitly, with `Player::X` being whoever moved first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WildBoard {
+    board: Board,
+    active_player: Player,
+}
+
+impl WildBoard {
+
+    /// Starts from `board`, with its shape and rules.  Every turn places one
+    /// mark, so the player to move is worked out from how many have been
+    /// placed.
+    pub fn new(board: Board) -> Self {
+        let n_marks = board.get_x_bitboard().union(&board.get_o_bitboard()).n_set();
+        let active_player = match n_marks % 2 {
+            0 => Player::X,
+            _ => Player::O,
+        };
+        WildBoard { board, active_player }
+    }
+
+    pub fn from_position(position: &str) -> Result<Self, ParseError> {
+        Ok(WildBoard::new(Board::from_position(position)?))
+    }
+
+    pub fn get_board(&self) -> Board {
+        self.board
+    }
+
+    fn has_line(&self) -> bool {
+        let shape = self.board.get_shape();
+        shape.is_victory(self.board.get_x_bitboard()) || shape.is_victory(self.board.get_o_bitboard())
+    }
+
+    /// A line of either symbol was completed by the player who moved last.
+    pub fn get_outcome(&self) -> Outcome {
+        let last_player = self.active_player.other();
+        match (self.has_line(), self.board.get_rules().is_misere()) {
+            (true, false) => Outcome::Victory(last_player),
+            (true, true) => Outcome::Victory(self.active_player),
+            (false, _) if self.board.is_full() => Outcome::Draw,
+            (false, _) => Outcome::InProgress,
+        }
+    }
+
+    pub fn get_active_player(&self) -> Option<Player> {
+        match self.get_outcome() {
+            Outcome::InProgress => Some(self.active_player),
+            _ => None,
+        }
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<WildMove> {
+        if self.get_active_player().is_none() {
+            return Vec::new();
+        }
+        let mut moves = Vec::new();
+        for square in self.board.get_legal_moves() {
+            moves.push(WildMove::new(square, Player::X));
+            moves.push(WildMove::new(square, Player::O));
+        }
+        moves
+    }
+
+    pub fn make_move(&mut self, player: Player, m: WildMove) -> Result<(), MoveError> {
+        match self.get_active_player() {
+            None => return Err(MoveError::GameOver),
+            Some(active_player) if active_player != player => return Err(MoveError::WrongTurn(player)),
+            Some(_) => (),
+        };
+        self.board.place_mark(m.symbol, m.square)?;
+        self.active_player = player.other();
+        Ok(())
+    }
+
+    pub fn with_move_made(&self, player: Player, m: WildMove) -> Result<Self, MoveError> {
+        let mut new_board = *self;
+        new_board.make_move(player, m)?;
+        Ok(new_board)
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.board.to_string()
+    }
+
+}
+
+impl Game for WildBoard {
+    type Move = WildMove;
+
+    fn get_outcome(&self) -> Outcome {
+        WildBoard::get_outcome(self)
+    }
+
+    fn get_active_player(&self) -> Option<Player> {
+        WildBoard::get_active_player(self)
+    }
+
+    fn get_legal_moves(&self) -> Vec<WildMove> {
+        WildBoard::get_legal_moves(self)
+    }
+
+    fn with_legal_move_made(&self, m: WildMove) -> Self {
+        let player = WildBoard::get_active_player(self).expect("Legal moves are only made in games in progress.");
+        self.with_move_made(player, m).expect("Move is legal by definition of get_legal_moves().")
+    }
+
+    /// Both players can play both symbols, so swapping the symbols gives an
+    /// equivalent position, as do the board's symmetries; the player to move
+    /// is normalized away.
+    fn get_transposition_key(&self) -> Self {
+        let swapped = Board::new_with_shape(self.board.get_o_bitboard(), self.board.get_x_bitboard(), self.board.get_shape())
+            .with_rules(self.board.get_rules())
+            .with_blocked_squares(self.board.get_blocked_bitboard())
+            .expect("Swapping the symbols leaves the blocked squares empty.");
+        let board = self.board.get_canonical_form().0.min(swapped.get_canonical_form().0);
+        WildBoard { board, active_player: Player::X }
+    }
+}
+
+
+#[cfg(test)]
+mod test_wild {
+    use super::*;
+    use crate::board::rules::Rules;
+    use crate::solver::alpha_beta::AlphaBetaSolver;
+    use crate::solver::evaluation::Evaluation;
+
+    #[test]
+    fn test_either_symbol_wins_for_the_player_who_completes_it() {
+        let mut board = WildBoard::from_position("OO_ XX_ ___").unwrap();
+        assert_eq!(board.get_active_player(), Some(Player::X));
+        assert_eq!(board.get_legal_moves().len(), 10);
+        assert_eq!(WildMove::from_string("O 0 2"), Ok(WildMove::new(Move::new(0, 2), Player::O)));
+        assert_eq!(WildMove::from_string("O02"), Ok(WildMove::new(Move::new(0, 2), Player::O)));
+        assert_eq!(WildMove::from_string("Z 0 2"), Err(ParseError::InvalidMove("Z 0 2".to_string())));
+        assert_eq!(board.make_move(Player::O, WildMove::new(Move::new(0, 2), Player::O)), Err(MoveError::WrongTurn(Player::O)));
+
+        board.make_move(Player::X, WildMove::new(Move::new(0, 2), Player::O)).unwrap();
+        assert_eq!(board.get_outcome(), Outcome::Victory(Player::X));
+        assert_eq!(board.get_board().get_o_bitboard().n_set(), 3);
+
+        let misere = WildBoard::new(Board::from_position("OO_ XX_ ___").unwrap().with_rules(Rules::misere()));
+        let misere = misere.with_move_made(Player::X, WildMove::new(Move::new(0, 2), Player::O)).unwrap();
+        assert_eq!(misere.get_outcome(), Outcome::Victory(Player::O));
+    }
+
+    #[test]
+    fn test_blocked_squares_are_kept_in_transposition_keys() {
+        for position in ["_#__X____", "___#X____", "____X#___"] {
+            let board = WildBoard::from_position(position).unwrap();
+            assert_eq!(Game::get_transposition_key(&board).get_board().get_blocked_bitboard().n_set(), 1);
+            assert_eq!(AlphaBetaSolver::from_board(board).get_evaluation(), Evaluation::win(Player::O, 5));
+        }
+    }
+
+    #[test]
+    fn test_first_player_wins_wild_tic_tac_toe() {
+        let (next_moves, evaluation) = AlphaBetaSolver::from_board(WildBoard::new(Board::empty())).get_next_moves_and_evaluation().unwrap();
+        assert_eq!(evaluation, Evaluation::win(Player::X, 7));
+        assert!(next_moves.contains(&WildMove::new(Move::new(1, 1), Player::X)));
+        assert!(next_moves.contains(&WildMove::new(Move::new(1, 1), Player::O)));
+    }
+
+}
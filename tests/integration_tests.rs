@@ -0,0 +1,121 @@
+use tic_tac_toe_solver::{
+    AlphaBetaSolver, Board, Evaluation, Illegality, Move, MoveError, ParseError, Player, Solver, SolverError, Tablebase,
+};
+
+#[test]
+fn test_solver_solves_tic_tac_toe() {
+    let mut solver = Solver::from_board(
+        Board::from_position(
+            "XOX
+            O_O
+            XOX",
+        ).unwrap()
+    );
+    assert_eq!(solver.get_next_moves(), Ok(vec![Move::new(1, 1)]));
+
+    let mut solver = Solver::from_board(
+        Board::from_position(
+            "X_X
+            O_O
+            ___",
+        ).unwrap()
+    );
+    assert_eq!(solver.get_next_moves(), Ok(vec![Move::new(0, 1)]));
+
+    let mut solver = Solver::from_board(
+        Board::from_position(
+            "XO_
+            _X_
+            ___",
+        ).unwrap()
+    );
+    assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(2, 2)], Evaluation::win(Player::X, 4))));
+    
+    let mut solver = Solver::from_board(
+        Board::from_position(
+            "XO_
+            _X_
+            __O",
+        ).unwrap()
+    );
+    assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(2, 0)], Evaluation::win(Player::X, 3))));
+
+    let mut solver = Solver::from_board(
+        Board::from_position(
+            "XO_
+            ___
+            ___",
+        ).unwrap()
+    );
+    assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(1, 0), Move::new(1, 1), Move::new(2, 0)], Evaluation::win(Player::X, 5))));
+
+    
+    let mut solver = Solver::from_board(
+        Board::from_position(
+            "XOX
+            _O_
+            __X",
+        ).unwrap()
+    );
+    assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(2, 1)], Evaluation::win(Player::O, 1))));
+
+    let mut solver = Solver::from_board(
+        Board::from_position(
+            "XO_
+            O__
+            XXO",
+        ).unwrap()
+    );
+    assert_eq!(solver.get_next_moves_and_evaluation(), Ok((vec![Move::new(0, 2), Move::new(1, 1), Move::new(1, 2)], Evaluation::draw())));
+
+}
+
+#[test]
+fn test_solvers_agree_from_outside_the_crate() {
+    let board = Board::from_position(
+        "XO_
+        _X_
+        __O",
+    ).unwrap();
+    let tablebase = Tablebase::generate();
+    let expected = Ok((vec![Move::new(1, 0), Move::new(2, 0)], Evaluation::win(Player::X, 3)));
+    assert_eq!(Solver::from_board(board).get_next_moves_and_evaluation(), expected);
+    assert_eq!(AlphaBetaSolver::from_board(board).get_next_moves_and_evaluation(), expected);
+    assert_eq!(tablebase.get_next_moves_and_evaluation(&board), expected);
+    assert_eq!(
+        AlphaBetaSolver::from_board(board).get_evaluation_and_line(),
+        tablebase.get_evaluation_and_line(&board).unwrap()
+    );
+}
+
+#[test]
+fn test_library_errors_are_typed() {
+    assert_eq!(Board::from_position("XO_"), Err(ParseError::InvalidPositionLength { position: "XO_".to_string(), length: 3 }));
+    assert_eq!(
+        Board::empty().with_move_made(Player::O, Move::new(0, 0)),
+        Err(MoveError::WrongTurn(Player::O))
+    );
+
+    let finished_board = Board::from_position(
+        "XXX
+        OO_
+        ___",
+    ).unwrap();
+    assert_eq!(AlphaBetaSolver::from_board(finished_board).get_next_moves(), Err(SolverError::GameOver));
+
+    let impossible_board = Board::from_position(
+        "OO_
+        ___
+        ___",
+    ).unwrap();
+    assert_eq!(impossible_board.validate(), Err(Illegality::PieceCountMismatch { n_x: 0, n_o: 2 }));
+    let error: Box<dyn std::error::Error> = Box::new(SolverError::from(Illegality::BothPlayersWon));
+    assert!(error.source().is_some());
+}
+
+#[test]
+fn test_evaluations_parse_from_cli_output() {
+    let evaluation = AlphaBetaSolver::from_board(Board::empty()).get_evaluation();
+    assert_eq!(evaluation.to_string(), "Drawn");
+    assert_eq!("O wins in 2".parse::<Evaluation>(), Ok(Evaluation::win(Player::O, 2)));
+}